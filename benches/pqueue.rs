@@ -104,7 +104,7 @@ pub fn deletion(c: &mut Criterion) {
 
                 bin_heap
             }, |mut bin_heap| {
-                while let Some(_) = bin_heap.pop() {}
+                while bin_heap.pop().is_some() {}
             }, BatchSize::SmallInput);
         });
 
@@ -121,7 +121,7 @@ pub fn deletion(c: &mut Criterion) {
                 
                 q
             }, |mut q| {
-                while let Some(_) = q.pop() {}
+                while q.pop().is_some() {}
             }, BatchSize::SmallInput);
         });
         
@@ -139,7 +139,7 @@ pub fn deletion(c: &mut Criterion) {
                 
                 q
             }, |mut q| {
-                while let Some(_) = q.pop() {}
+                while q.pop().is_some() {}
             }, BatchSize::SmallInput);
         });
         
@@ -156,7 +156,7 @@ pub fn deletion(c: &mut Criterion) {
                 
                 q
             }, |mut q| {
-                while let Some(_) = q.pop() {}
+                while q.pop().is_some() {}
             }, BatchSize::SmallInput);
         });
     }
@@ -70,6 +70,18 @@ pub fn insertion(c: &mut Criterion) {
                 }
             });
         });
+
+        group.bench_with_input(BenchmarkId::new("svlsm", size), &vec, |b, v| {
+            b.iter(|| {
+                use dynamization::sorted_vec::SVLsmMap;
+
+                let mut m = SVLsmMap::<i32,i32>::new();
+
+                for x in v.chunks(2) {
+                    m.insert(x[0], x[1]);
+                }
+            });
+        });
     }
 
     group.finish();
@@ -159,12 +171,123 @@ pub fn deletion(c: &mut Criterion) {
                 for x in v { m.remove(x); }
             }, BatchSize::SmallInput);
         });
+
+        group.bench_with_input(BenchmarkId::new("svlsm", size), &vec, |b, v| {
+            b.iter_batched(|| {
+                use dynamization::sorted_vec::SVLsmMap;
+
+                let mut m = SVLsmMap::<i32,i32>::new();
+
+                for x in v.chunks(2) {
+                    m.insert(x[0], x[1]);
+                }
+                
+                (m, v)
+            }, |(mut m, v)| {
+                for x in v { m.remove(*x); }
+            }, BatchSize::SmallInput);
+        });
+    }
+
+    group.finish();
+}
+
+
+pub fn search(c: &mut Criterion) {
+    use rand::{ Rng, SeedableRng };
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+    let mut group = c.benchmark_group("search");
+
+    for size in &[10, 100, 1_000, 10_000, 100_000, 1_000_000] {
+        let size = *size;
+
+        let mut vec = Vec::<i32>::new();
+        
+        for _ in 0..size {
+            vec.push(rng.gen());
+        }
+
+        // Even keys are present, odd keys are absent.
+        let keys: Vec<i32> = vec.iter().map(|x| x & !1).collect();
+        let queries: Vec<i32> = vec.iter().rev().map(|x| x ^ 1).collect();
+       
+        group.bench_with_input(BenchmarkId::new("btree", size), &queries, |b, q| {
+            use std::collections::BTreeMap;
+
+            let mut m = BTreeMap::<i32,i32>::new();
+
+            for x in &keys {
+                m.insert(*x, *x);
+            }
+
+            b.iter(|| {
+                q.iter().filter(|x| m.contains_key(x)).count()
+            });
+        });
+
+        group.bench_with_input(BenchmarkId::new("svmap", size), &queries, |b, q| {
+            use dynamization::sorted_vec::SVMap;
+
+            let mut m = SVMap::<i32,i32>::new();
+
+            for x in &keys {
+                m.insert(*x, *x);
+            }
+
+            b.iter(|| {
+                q.iter().filter(|x| m.contains_key(x)).count()
+            });
+        });
+
+        group.bench_with_input(BenchmarkId::new("svmap_get_many", size), &queries, |b, q| {
+            use dynamization::sorted_vec::SVMap;
+
+            let mut m = SVMap::<i32,i32>::new();
+
+            for x in &keys {
+                m.insert(*x, *x);
+            }
+
+            b.iter(|| {
+                m.get_many(q).iter().filter(|x| x.is_some()).count()
+            });
+        });
+
+        group.bench_with_input(BenchmarkId::new("svmap_bloom", size), &queries, |b, q| {
+            use dynamization::sorted_vec::{ SVMap, Bloom10 };
+
+            let mut m = SVMap::<i32,i32>::with_filter::<Bloom10>();
+
+            for x in &keys {
+                m.insert(*x, *x);
+            }
+
+            b.iter(|| {
+                q.iter().filter(|x| m.contains_key(x)).count()
+            });
+        });
+
+        group.bench_with_input(BenchmarkId::new("svlsm", size), &queries, |b, q| {
+            use dynamization::sorted_vec::SVLsmMap;
+
+            let mut m = SVLsmMap::<i32,i32>::new();
+
+            for x in &keys {
+                m.insert(*x, *x);
+            }
+
+            b.iter(|| {
+                q.iter().filter(|x| m.contains_key(x)).count()
+            });
+        });
     }
 
     group.finish();
 }
 
 
-criterion_group!(benches, insertion, deletion);
+criterion_group!(benches, insertion, deletion, search);
 criterion_main!(benches);
 
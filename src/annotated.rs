@@ -0,0 +1,157 @@
+//! Per-unit metadata.
+//!
+//! An [`Annotated`] unit carries a user-defined [`Summary`] of its content
+//! (e.g. minimal/maximal keys, a count or a checksum) which is computed on
+//! construction and combined on merges. Queries can use summaries to skip
+//! units without probing them (see [`Dynamic::units_with_meta`]).
+//!
+//! ```
+//! use dynamization::{ Static, Singleton, Dynamic };
+//! use dynamization::annotated::{ Annotated, Summary };
+//!
+//! struct Bag(Vec<i32>);
+//!
+//! impl Static for Bag {
+//!     fn len(&self) -> usize { self.0.len() }
+//!
+//!     fn merge_with(mut self, other: Self) -> Self {
+//!         self.0.extend(other.0);
+//!         self
+//!     }
+//! }
+//!
+//! impl Singleton for Bag {
+//!     type Item = i32;
+//!
+//!     fn singleton(item: i32) -> Self { Bag(vec![item]) }
+//! }
+//!
+//! #[derive(Clone, Copy)]
+//! struct MinMax(i32, i32);
+//!
+//! impl Summary<Bag> for MinMax {
+//!     fn summarize(bag: &Bag) -> Self {
+//!         MinMax(*bag.0.iter().min().unwrap(), *bag.0.iter().max().unwrap())
+//!     }
+//!
+//!     fn combine(self, other: Self) -> Self {
+//!         MinMax(self.0.min(other.0), self.1.max(other.1))
+//!     }
+//! }
+//!
+//! let mut dynamic = Dynamic::<Annotated<Bag, MinMax>>::new();
+//!
+//! for x in 0..100 {
+//!     dynamic.insert(x);
+//! }
+//!
+//! let contains = |x: i32| dynamic.units_with_meta()
+//!     .filter(|(_, meta)| meta.0 <= x && x <= meta.1)
+//!     .any(|(bag, _)| bag.0.contains(&x));
+//!
+//! assert!(contains(42));
+//! assert!(!contains(100));
+//! ```
+
+use crate::*;
+
+
+/// Metadata summarizing a unit content.
+pub trait Summary<Container>: Sized {
+    /// Computes the summary of a freshly constructed unit.
+    fn summarize(container: &Container) -> Self;
+
+    /// Combines the summaries of two merged units.
+    fn combine(self, other: Self) -> Self;
+}
+
+
+/// A unit annotated with a summary of its content.
+#[derive(Clone, Debug)]
+pub struct Annotated<Container, M> {
+    container: Container,
+    meta: M,
+}
+
+impl<Container, M: Summary<Container>> Annotated<Container, M> {
+    /// Annotates a container with its summary.
+    pub fn new(container: Container) -> Self {
+        let meta = M::summarize(&container);
+
+        Annotated { container, meta }
+    }
+}
+
+impl<Container, M> Annotated<Container, M> {
+    /// The partial container itself.
+    pub fn container(&self) -> &Container {
+        &self.container
+    }
+
+    /// The summary of the partial container.
+    pub fn meta(&self) -> &M {
+        &self.meta
+    }
+
+    /// Drops the summary.
+    pub fn into_inner(self) -> Container {
+        self.container
+    }
+}
+
+impl<Container: Static, M: Summary<Container>> Static for Annotated<Container, M> {
+    fn len(&self) -> usize {
+        self.container.len()
+    }
+
+    fn merge_with(self, other: Self) -> Self {
+        Annotated {
+            container: self.container.merge_with(other.container),
+            meta: self.meta.combine(other.meta),
+        }
+    }
+}
+
+impl<Container: Singleton, M: Summary<Container>> Singleton for Annotated<Container, M> {
+    type Item = Container::Item;
+
+    fn singleton(item: Self::Item) -> Self {
+        Annotated::new(Container::singleton(item))
+    }
+}
+
+impl<Container: Insert, M: Summary<Container>> Insert for Annotated<Container, M> {
+    /// Recomputes the summary from scratch: intended only for small buffers.
+    fn insert(&mut self, item: Self::Item) {
+        self.container.insert(item);
+        self.meta = M::summarize(&self.container);
+    }
+}
+
+
+impl<Container, M, S> Dynamic<Annotated<Container, M>, S> where
+    Container: Static,
+    M: Summary<Container>,
+    S: Strategy,
+{
+    /// Iterator over all the partial containers paired with their summaries.
+    pub fn units_with_meta(&self) -> UnitsWithMeta<'_, Container, M> {
+        UnitsWithMeta {
+            units: self.units(),
+        }
+    }
+}
+
+
+/// Iterator over all the partial containers paired with their summaries.
+pub struct UnitsWithMeta<'a, Container, M> {
+    units: Units<'a, Annotated<Container, M>>,
+}
+
+impl<'a, Container, M> Iterator for UnitsWithMeta<'a, Container, M> {
+    type Item = (&'a Container, &'a M);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.units.next().map(|unit| (&unit.container, &unit.meta))
+    }
+}
@@ -0,0 +1,209 @@
+//! A mutable write buffer in front of [`Dynamic`].
+//!
+//! Each [`Dynamic::insert`] makes a single-element unit and immediately
+//! merges it with other units. [`Buffered`] instead keeps up to
+//! [`capacity`](Buffered::capacity) recent items in a small container
+//! updated in place (see [`Insert`]) and adds this container as a whole unit
+//! when it's full.
+//!
+//! The buffer is yielded by [`units`](Buffered::units)
+//! as any other unit, so the queries need not to care about it.
+
+use crate::*;
+
+
+/// A dynamic version of `Container` with a write buffer.
+#[derive(Clone, Debug)]
+pub struct Buffered<Container, S = strategy::Binary> {
+    buffer: Option<Container>,
+    dynamic: Dynamic<Container, S>,
+    capacity: usize,
+}
+
+impl<Container: Static, S: Strategy> Buffered<Container, S> {
+    /// Buffer capacity used by [`new`](Buffered::new).
+    pub const DEFAULT_CAPACITY: usize = 32;
+
+    /// A new container with the default buffer capacity.
+    pub fn new() -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
+    }
+
+    /// A new container with a specified buffer capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert!(capacity > 0);
+
+        Buffered {
+            buffer: None,
+            dynamic: Dynamic::new(),
+            capacity,
+        }
+    }
+
+    /// Maximal size of the buffer.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Adds a new unit (partial container) bypassing the buffer.
+    pub fn add_unit(&mut self, container: Container) {
+        self.dynamic.add_unit(container);
+    }
+
+    /// Adds the buffer (if nonempty) to the underlying [`Dynamic`].
+    pub fn flush(&mut self) {
+        if let Some(buffer) = self.buffer.take() {
+            if !buffer.is_empty() {
+                self.dynamic.add_unit(buffer);
+            }
+        }
+    }
+
+    /// Total size of the container including the buffer.
+    pub fn len(&self) -> usize {
+        self.units().map(|x| x.len()).sum()
+    }
+
+    /// Returns `true` if there are no elements.
+    ///
+    /// Warning: this function queries all the units.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The write buffer if any.
+    pub fn buffer(&self) -> Option<&Container> {
+        self.buffer.as_ref()
+    }
+
+    /// The write buffer if any. Unique-reference version.
+    pub fn buffer_mut(&mut self) -> Option<&mut Container> {
+        self.buffer.as_mut()
+    }
+
+    /// Iterator over all the partial containers starting with the buffer.
+    /// Shared-reference version.
+    pub fn units(&self) -> BufferedUnits<'_, Container> {
+        BufferedUnits {
+            buffer: self.buffer.as_ref(),
+            units: self.dynamic.units(),
+        }
+    }
+
+    /// Iterator over all the partial containers starting with the buffer.
+    /// Unique-reference version.
+    pub fn units_mut(&mut self) -> BufferedUnitsMut<'_, Container> {
+        BufferedUnitsMut {
+            buffer: self.buffer.as_mut(),
+            units: self.dynamic.units_mut(),
+        }
+    }
+
+    /// Collects all the partial containers (including the buffer)
+    /// into a single one.
+    ///
+    /// Returns `None` if there are no units.
+    pub fn try_collect(mut self) -> Option<Container> {
+        self.flush();
+        self.dynamic.try_collect()
+    }
+
+    /// Clears the buffer and all the partial containers.
+    pub fn clear(&mut self) {
+        self.buffer = None;
+        self.dynamic.clear();
+    }
+}
+
+impl<Container: Static + Insert, S: Strategy> Buffered<Container, S> {
+    /// Inserts a single item into the buffer.
+    ///
+    /// Flushes the buffer beforehand if it's full, so the item always stays 
+    /// in the buffer after the call.
+    pub fn insert(&mut self, item: Container::Item) {
+        if self.buffer.as_ref().map_or(false, |buffer| buffer.len() >= self.capacity) {
+            self.flush();
+        }
+
+        match &mut self.buffer {
+            Some(buffer) => { buffer.insert(item); }
+            None => { self.buffer = Some(Container::singleton(item)); }
+        }
+    }
+}
+
+impl<Container: Static, S: Strategy> Default for Buffered<Container, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+/// Shared-reference iterator over the buffer and all the partial containers.
+pub struct BufferedUnits<'a, Container> {
+    buffer: Option<&'a Container>,
+    units: Units<'a, Container>,
+}
+
+impl<'a, Container> Iterator for BufferedUnits<'a, Container> {
+    type Item = &'a Container;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buffer.take().or_else(|| self.units.next())
+    }
+}
+
+impl<'a, Container> DoubleEndedIterator for BufferedUnits<'a, Container> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.units.next_back().or_else(|| self.buffer.take())
+    }
+}
+
+
+/// Unique-reference iterator over the buffer and all the partial containers.
+pub struct BufferedUnitsMut<'a, Container> {
+    buffer: Option<&'a mut Container>,
+    units: UnitsMut<'a, Container>,
+}
+
+impl<'a, Container> Iterator for BufferedUnitsMut<'a, Container> {
+    type Item = &'a mut Container;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buffer.take().or_else(|| self.units.next())
+    }
+}
+
+impl<'a, Container> DoubleEndedIterator for BufferedUnitsMut<'a, Container> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.units.next_back().or_else(|| self.buffer.take())
+    }
+}
+
+
+/// Owning iterator over the buffer and all the partial containers.
+pub struct BufferedIntoIter<Container> {
+    buffer: Option<Container>,
+    units: DynamicIntoIter<Container>,
+}
+
+impl<Container> Iterator for BufferedIntoIter<Container> {
+    type Item = Container;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.buffer.take().or_else(|| self.units.next())
+    }
+}
+
+
+impl<Container, S> IntoIterator for Buffered<Container, S> {
+    type Item = Container;
+    type IntoIter = BufferedIntoIter<Container>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        BufferedIntoIter {
+            buffer: self.buffer,
+            units: self.dynamic.into_iter(),
+        }
+    }
+}
@@ -171,6 +171,11 @@ pub trait Static where Self: Sized {
     /// [`SkewBinary`](strategy::SkewBinary).
     fn len(&self) -> usize;
 
+    /// Returns `true` if the container has no elements.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     /// Merges two containers into one.
     ///
     /// One possible way to implement this is to collect both containers and 
@@ -188,6 +193,15 @@ pub trait Singleton where Self: Sized {
     fn singleton(item: Self::Item) -> Self;
 }
 
+/// A trait for containers supporting a (possibly slow) in-place insertion.
+///
+/// Such containers can be used as write buffers in 
+/// [`Buffered`](buffered::Buffered).
+pub trait Insert: Singleton {
+    /// Inserts a single item into the container.
+    fn insert(&mut self, item: Self::Item);
+}
+
 
 
 pub mod strategy;
@@ -245,33 +259,44 @@ impl<Container: Static, S: Strategy> Dynamic<Container, S> {
     }
 
     /// Iterator over all the partial containers. Shared-reference version.
-    pub fn units(&self) -> Units<Container>/*impl Iterator<Item=&Container>*/ {
+    ///
+    /// The units go in the order of their positions in the strategy: 
+    /// e.g. for the [`Binary`](strategy::Binary) strategy from the smallest 
+    /// to the largest.
+    pub fn units(&self) -> Units<'_, Container>/*impl Iterator<Item=&Container>*/ {
         Units {
             units: self.units.iter()//.filter_map(|x| x.as_ref())
         }
     }
 
     /// Iterator over all the partial containers. Unique-reference version.
-    pub fn units_mut(&mut self) -> UnitsMut<Container>/*impl Iterator<Item=&mut Container>*/ 
+    pub fn units_mut(&mut self) -> UnitsMut<'_, Container>/*impl Iterator<Item=&mut Container>*/ 
     {
         UnitsMut {
             units: self.units.iter_mut()//.filter_map(|x| x.as_mut())
         }
     }
 
+    /// All the unit slots of the strategy including the empty ones.
+    #[cfg(any(feature = "sorted_vec", doc))]
+    pub(crate) fn slots(&self) -> &[Option<Container>] {
+        &self.units
+    }
+
+    /// All the unit slots of the strategy including the empty ones.
+    /// Unique-reference version.
+    #[cfg(any(feature = "sorted_vec", doc))]
+    pub(crate) fn slots_mut(&mut self) -> &mut [Option<Container>] {
+        &mut self.units
+    }
+
     /// Collects all the partial containers into a single one.
     ///
     /// Returns `None` if there are no units.
     pub fn try_collect(self) -> Option<Container> {
-        let mut iter = self.units.into_iter().filter_map(|x| x);
-
-        match iter.next() {
-            None => None,
+        let mut iter = self.units.into_iter().flatten();
 
-            Some(first) => {
-                Some( iter.fold(first, |acc, x| acc.merge_with(x)) )
-            }
-        }
+        iter.next().map(|first| iter.fold(first, |acc, x| acc.merge_with(x)))
     }
 
     /// Clears all the partial containers.
@@ -280,6 +305,27 @@ impl<Container: Static, S: Strategy> Dynamic<Container, S> {
             *unit = None;
         }
     }
+
+    /// Retains only the units specified by the predicate.
+    ///
+    /// The removed units are simply dropped: no merges are performed.
+    pub fn retain_units<F>(&mut self, mut f: F) where
+        F: FnMut(&mut Container) -> bool
+    {
+        for unit in self.units.iter_mut() {
+            if let Some(container) = unit {
+                if !f(container) {
+                    *unit = None;
+                }
+            }
+        }
+    }
+}
+
+impl<Container: Static, S: Strategy> Default for Dynamic<Container, S> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<Container: Static+Singleton, S: Strategy> Dynamic<Container, S> {
@@ -311,6 +357,18 @@ impl<'a, Container> Iterator for Units<'a, Container> {
     }
 }
 
+impl<'a, Container> DoubleEndedIterator for Units<'a, Container> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.units.next_back().map(|x| x.as_ref()) {
+                Some(None) => {}
+                Some(Some(item)) => { return Some(item); }
+                None => { return None; }
+            }
+        }
+    }
+}
+
 
 /// Unique-reference iterator over all the partial containers.
 pub struct UnitsMut<'a, Container> {
@@ -331,6 +389,18 @@ impl<'a, Container> Iterator for UnitsMut<'a, Container> {
     }
 }
 
+impl<'a, Container> DoubleEndedIterator for UnitsMut<'a, Container> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.units.next_back().map(|x| x.as_mut()) {
+                Some(None) => {}
+                Some(Some(item)) => { return Some(item); }
+                None => { return None; }
+            }
+        }
+    }
+}
+
 
 /// Owning iterator over all the partial containers.
 pub struct DynamicIntoIter<Container> {
@@ -364,5 +434,10 @@ impl<Container, S> IntoIterator for Dynamic<Container, S> {
 }
 
 
+pub mod annotated;
+pub mod buffered;
+pub mod tiered;
+pub mod windowed;
+
 #[cfg(any(feature = "sorted_vec", doc))]
 pub mod sorted_vec;
@@ -1,14 +1,82 @@
 //! Sorted `Vec`. Can be used as a priority queue or as an associative array. 
 //! __Requires feature `sorted_vec`__.
 //!
-//! Defines an opaque [`SortedVec`] type and two containers:
+//! Defines an opaque [`SortedVec`] type and several containers:
 //! * [`SVQueue`] analogous to [`BinaryHeap`](alloc::collections::BinaryHeap)
+//!   (and [`SVMinQueue`] popping the minimum)
 //! * [`SVMap`] analogous to [`BTreeMap`](alloc::collections::BTreeMap)
+//! * [`SVSet`] analogous to [`BTreeSet`](alloc::collections::BTreeSet)
+//! * [`SVMultiMap`] and [`SVBag`] allowing duplicate keys
+//! * [`SVCola`] and [`SVColaMap`]: a sorted multiset and a map with
+//!   fractional cascading between the units
+//! * [`SVLsmMap`]: a map with blind writes (a log-structured merge tree)
+//! * [`SVMergeMap`]: a map with blind updates combined by a [`MergeOperator`]
+//! * [`SVStrMap`]: a map with string keys stored compactly (front coding)
+//!
+//! [`SVQueueBy`] and [`SVMapBy`] are ordered by a [`Compare`] comparator
+//! instead of `Ord`.
 
 
 use crate::*;
+use crate::buffered::Buffered;
 use alloc::vec;
 
+mod batch;
+
+mod cola;
+pub use cola::{ SVCola, SVColaMap };
+
+mod compare;
+pub use compare::{
+    Compare, Natural, Reversed, Ordered,
+    SortedVecBy, SVQueueBy, SVQueueByPeekMut, SVMinQueue,
+    SVMapBy, SVMapByIter, SVMapByIterMut, SVMapByKeys, SVMapByValues, SVMapByValuesMut,
+    SVMapByRange, SVMapByRangeMut,
+};
+
+mod entry;
+pub use entry::{ Entry, OccupiedEntry, VacantEntry };
+
+mod filter;
+pub use filter::{ Filter, NoFilter, Bloom, Bloom10 };
+
+mod lsm;
+pub use lsm::SVLsmMap;
+
+mod multi;
+pub use multi::{ SVMultiMap, SVMultiMapGetAll, SVMultiMapIter, SVBag, SVBagIter };
+
+mod prefix;
+pub use prefix::KeyPrefix;
+
+mod rank;
+
+mod rebuild;
+pub use rebuild::RebuildPolicy;
+
+mod remove;
+pub use remove::SVMapExtractIf;
+
+mod traits;
+
+mod set;
+pub use set::{
+    SVSet, SVSetIter, SVSetIntoIter, SVSetRange,
+    SVSetUnion, SVSetIntersection, SVSetDifference, SVSetSymmetricDifference,
+};
+
+mod strmap;
+pub use strmap::{ SVStrMap, SVStrMapIter };
+
+mod merge;
+pub use merge::{ SVMergeMap, MergeOperator, Sum, Concat };
+
+mod iter;
+pub use iter::{
+    SVMapIter, SVMapIterMut, SVMapKeys, SVMapValues, SVMapValuesMut, SVMapKV,
+    SVMapRange, SVMapRangeMut, SVMapJoin,
+};
+
 /// An opaque struct with an unspecified interface.
 ///
 /// Obviously can't be used directly.
@@ -27,85 +95,123 @@ impl<T: Ord> Static for SortedVec<T> {
     }
 
     fn merge_with(self, other: Self) -> Self {
-        let a = self.vec;
-        let b = other.vec;
-
-        let mut vec: Vec<T> = Vec::with_capacity(a.len() + b.len());
-        
-        let vec_ptr = vec.as_mut_ptr();
-        let mut i = 0;
-
-        let mut a = a.into_iter();
-        let mut b = b.into_iter();
-
-        let mut maybe_x = a.next();
-        let mut maybe_y = b.next();
-
-        let branch;
-
-        loop {
-            match (maybe_x, maybe_y) {
-                (Some(x), Some(y)) => {
-                    if x < y {
-                        unsafe { vec_ptr.add(i).write(x); }
-                        maybe_x = a.next();
-                        maybe_y = Some(y);
-                    } else {
-                        unsafe { vec_ptr.add(i).write(y); }
-                        maybe_x = Some(x);
-                        maybe_y = b.next();
-                    }
+        SortedVec { vec: merge_vecs(self.vec, other.vec) }
+    }
+}
 
-                    i += 1;
+/// Merges two sorted vectors with unique elements into one combining 
+/// the equal elements.
+///
+/// `combine(x, y)` gets `x` from `a` and `y` from `b` and can drop both.
+fn merge_vecs_with<T: Ord, C>(a: Vec<T>, b: Vec<T>, mut combine: C) -> Vec<T> where
+    C: FnMut(T, T) -> Option<T>
+{
+    let mut vec = Vec::with_capacity(a.len() + b.len());
+
+    let mut a = a.into_iter().peekable();
+    let mut b = b.into_iter().peekable();
+
+    loop {
+        let item = match (a.peek(), b.peek()) {
+            (None, None) => { break; }
+            (Some(_), None) => a.next(),
+            (None, Some(_)) => b.next(),
+
+            (Some(x), Some(y)) => match x.cmp(y) {
+                core::cmp::Ordering::Less => a.next(),
+                core::cmp::Ordering::Greater => b.next(),
+
+                core::cmp::Ordering::Equal => {
+                    a.next().zip(b.next()).and_then(|(x, y)| combine(x, y))
                 }
+            }
+        };
+
+        vec.extend(item);
+    }
+
+    vec
+}
+
+/// Merges two sorted vectors into one.
+///
+/// Of two equal elements the one from `b` goes first.
+fn merge_vecs<T: Ord>(a: Vec<T>, b: Vec<T>) -> Vec<T> {
+    let mut vec: Vec<T> = Vec::with_capacity(a.len() + b.len());
+    
+    let vec_ptr = vec.as_mut_ptr();
+    let mut i = 0;
+
+    let mut a = a.into_iter();
+    let mut b = b.into_iter();
+
+    let mut maybe_x = a.next();
+    let mut maybe_y = b.next();
 
-                (Some(x), None) => {
+    let branch;
+
+    loop {
+        match (maybe_x, maybe_y) {
+            (Some(x), Some(y)) => {
+                if x < y {
                     unsafe { vec_ptr.add(i).write(x); }
-                    i += 1;
-                    branch = Branch::A;
-                    break;
-                }
-                
-                (None, Some(y)) => {
+                    maybe_x = a.next();
+                    maybe_y = Some(y);
+                } else {
                     unsafe { vec_ptr.add(i).write(y); }
-                    i += 1;
-                    branch = Branch::B;
-                    break;
+                    maybe_x = Some(x);
+                    maybe_y = b.next();
                 }
 
-                (None, None) => { 
-                    branch = Branch::None;
-                    break; 
-                }
+                i += 1;
             }
-        }
 
-        match branch {
-            Branch::A => {
-                for x in a {
-                    unsafe { vec_ptr.add(i).write(x); }
-                    i += 1;
-                }
+            (Some(x), None) => {
+                unsafe { vec_ptr.add(i).write(x); }
+                i += 1;
+                branch = Branch::A;
+                break;
             }
             
-            Branch::B => {
-                for x in b {
-                    unsafe { vec_ptr.add(i).write(x); }
-                    i += 1;
-                }
+            (None, Some(y)) => {
+                unsafe { vec_ptr.add(i).write(y); }
+                i += 1;
+                branch = Branch::B;
+                break;
             }
 
-            Branch::None => {}
+            (None, None) => { 
+                branch = Branch::None;
+                break; 
+            }
         }
+    }
 
-        assert!(i == vec.capacity());
-        // Safety: the assertion above; also this assertion almost guarantees
-        // that the memory accesses in the loops above (.add(i).write(...))
-        // do not touch unallocated memory.
-        unsafe { vec.set_len(i); }
+    match branch {
+        Branch::A => {
+            for x in a {
+                unsafe { vec_ptr.add(i).write(x); }
+                i += 1;
+            }
+        }
+        
+        Branch::B => {
+            for x in b {
+                unsafe { vec_ptr.add(i).write(x); }
+                i += 1;
+            }
+        }
 
-        SortedVec { vec }
+        Branch::None => {}
     }
+
+    assert!(i == vec.capacity());
+    // Safety: the assertion above; also this assertion almost guarantees
+    // that the memory accesses in the loops above (.add(i).write(...))
+    // do not touch unallocated memory.
+    unsafe { vec.set_len(i); }
+
+    vec
 }
 
 impl<T> Singleton for SortedVec<T> {
@@ -116,6 +222,14 @@ impl<T> Singleton for SortedVec<T> {
     }
 }
 
+impl<T: Ord> Insert for SortedVec<T> {
+    fn insert(&mut self, item: Self::Item) {
+        let index = self.vec.partition_point(|x| x <= &item);
+
+        self.vec.insert(index, item);
+    }
+}
+
 impl<T: Ord> core::iter::FromIterator<T> for SortedVec<T> {
     fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
         let mut vec = iter.into_iter().collect::<Vec<_>>();
@@ -129,14 +243,15 @@ impl<T: Ord> core::iter::FromIterator<T> for SortedVec<T> {
 
 /// A max-priority queue based on a sorted vector.
 ///
-/// Currently provides only basic operations.
-///
 /// Has slow insertions (4-8 times slower than those of 
 /// [`BinaryHeap`](`alloc::collections::BinaryHeap`)) but fast deletions 
 /// (2-3 times faster then [`BinaryHeap`](alloc::collections::BinaryHeap) ones).
+///
+/// Recent items are kept in a small [`Buffered`] write buffer instead of 
+/// single-element units.
 #[derive(Clone, Debug)]
 pub struct SVQueue<T, S = strategy::Binary> {
-    dynamic: Dynamic<SortedVec<T>, S>,
+    dynamic: Buffered<SortedVec<T>, S>,
     len: usize,
 }
 
@@ -145,7 +260,7 @@ impl<T: Ord> SVQueue<T> {
     /// Uses the [`Binary`](strategy::Binary) strategy.
     pub fn new() -> Self {
         SVQueue {
-            dynamic: Dynamic::new(),
+            dynamic: Buffered::new(),
             len: 0,
         }
     }
@@ -166,7 +281,17 @@ impl<T: Ord> SVQueue<T> {
     /// [peeks](SVQueue::peek) and [deletions](SVQueue::pop).
     pub fn with_strategy<S: Strategy>() -> SVQueue<T, S> {
         SVQueue {
-            dynamic: Dynamic::new(),
+            dynamic: Buffered::new(),
+            len: 0,
+        }
+    }
+}
+
+
+impl<T: Ord, S: Strategy> Default for SVQueue<T, S> {
+    fn default() -> Self {
+        SVQueue {
+            dynamic: Buffered::new(),
             len: 0,
         }
     }
@@ -198,10 +323,39 @@ impl<T: Ord, S: Strategy> SVQueue<T, S> {
     }
 
     /// Exclusively returns the current maximum.
-    pub fn peek_mut(&mut self) -> Option<&mut T> {
-        self.dynamic.units_mut()
-            .filter_map(|unit| unit.vec.last_mut())
-            .max()
+    ///
+    /// The item is moved back into place when the guard is dropped, so it
+    /// can be changed arbitrarily.
+    ///
+    /// ```
+    /// # #[cfg(feature="sorted_vec")] {
+    /// use dynamization::sorted_vec::{ SVQueue, SVQueuePeekMut };
+    ///
+    /// let mut pqueue = SVQueue::new();
+    ///
+    /// for x in [1, 5, 3] {
+    ///     pqueue.push(x);
+    /// }
+    ///
+    /// *pqueue.peek_mut().unwrap() = 2;
+    /// assert_eq!(pqueue.peek(), Some(&3));
+    ///
+    /// assert_eq!(pqueue.peek_mut().map(SVQueuePeekMut::pop), Some(3));
+    /// assert_eq!(pqueue.pop(), Some(2));
+    /// # }
+    /// ```
+    pub fn peek_mut(&mut self) -> Option<SVQueuePeekMut<'_, T>> {
+        let SVQueue { dynamic, len } = self;
+
+        let unit = dynamic.units_mut()
+            .max_by(|u1, u2| {
+                u1.vec.last().cmp(&u2.vec.last())
+            })?;
+
+        let item = unit.vec.pop()?;
+        *len -= 1;
+
+        Some(SVQueuePeekMut { unit, len, item: Some(item) })
     }
 
     /// Removes the current maximum from the container.
@@ -228,6 +382,48 @@ impl<T: Ord, S: Strategy> SVQueue<T, S> {
     }
 }
 
+/// A guard returned by [`SVQueue::peek_mut`].
+///
+/// Holds the maximum out of the queue and puts it back on drop.
+/// If the guard is leaked, the item is lost.
+#[derive(Debug)]
+pub struct SVQueuePeekMut<'a, T: Ord> {
+    unit: &'a mut SortedVec<T>,
+    len: &'a mut usize,
+    item: Option<T>,
+}
+
+impl<'a, T: Ord> SVQueuePeekMut<'a, T> {
+    /// Removes the peeked item from the queue.
+    pub fn pop(mut this: Self) -> T {
+        this.item.take().unwrap()
+    }
+}
+
+impl<'a, T: Ord> core::ops::Deref for SVQueuePeekMut<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.item.as_ref().unwrap()
+    }
+}
+
+impl<'a, T: Ord> core::ops::DerefMut for SVQueuePeekMut<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.item.as_mut().unwrap()
+    }
+}
+
+impl<'a, T: Ord> Drop for SVQueuePeekMut<'a, T> {
+    fn drop(&mut self) {
+        if let Some(item) = self.item.take() {
+            // Still the last one unless it has been decreased.
+            self.unit.insert(item);
+            *self.len += 1;
+        }
+    }
+}
+
 #[test]
 fn test_svqueue_len() {
     let some_numbers = vec![1,4,6,2,1,5,7,4,3,2,7,8];
@@ -256,21 +452,193 @@ fn test_svqueue_len() {
 
 /// An associative array based on a sorted vector.
 ///
-/// Currently provides only basic operations.
-///
 /// Much slower than [`BTreeMap`](`alloc::collections::BTreeMap`) so useful 
 /// only for nonpractical purposes (mainly as an example of implementing 
 /// a dynamized container).
-#[derive(Clone, Debug)]
-pub struct SVMap<K, V, S = strategy::Binary> {
-    dynamic: Dynamic<SortedVec<SVPair<K, V>>, S>,
+///
+/// Lookups skip the units whose key ranges (from the first key to the last 
+/// one) don't contain the key, so workloads with disjoint or monotonic keys 
+/// (e.g. increasing IDs) are much faster.
+///
+/// Each unit can also have a key [`Filter`] (e.g. a [`Bloom`] filter) built on 
+/// merges: lookups skip the units which definitely don't contain the key.
+/// No filters are built by default ([`NoFilter`]).
+///
+/// Removals leave tombstones in place. Merges of units drop them, and 
+/// a [`RebuildPolicy`] decides when to drop them from the other units.
+#[derive(Clone)]
+pub struct SVMap<K, V, S = strategy::Binary, F = NoFilter> {
+    dynamic: Buffered<SVMapUnit<K, V, F>, S>,
     len: usize,
-    free_count: usize,
+    policy: RebuildPolicy,
 }
 
 #[derive(Clone, Debug)]
 struct SVPair<K, V>(K, Option<V>);
 
+/// A sorted vector of entries with a key filter.
+#[derive(Clone, Debug)]
+struct SVMapUnit<K, V, F> {
+    vec: Vec<SVPair<K, V>>,
+    filter: F,
+
+    /// The number of tombstones in `vec` outside of `dead`.
+    free_count: usize,
+
+    /// Sorted disjoint spans of `vec` removed by a range removal (range 
+    /// tombstones). Dropped on merges.
+    dead: Vec<core::ops::Range<usize>>,
+}
+
+/// The complement of the sorted disjoint `dead` spans in `0..len`.
+fn live_spans(dead: &[core::ops::Range<usize>], len: usize) -> impl Iterator<Item = core::ops::Range<usize>> + '_ {
+    let starts = core::iter::once(0).chain(dead.iter().map(|span| span.end));
+    let ends = dead.iter().map(|span| span.start).chain(core::iter::once(len));
+
+    starts.zip(ends).filter(|(start, end)| start < end).map(|(start, end)| start..end)
+}
+
+impl<K, V, F> SVMapUnit<K, V, F> {
+    fn is_dead(&self, index: usize) -> bool {
+        self.dead.iter().any(|span| span.contains(&index))
+    }
+
+    /// The parts of `vec` not covered by range tombstones.
+    fn live_slices(&self) -> impl Iterator<Item = &[SVPair<K, V>]> {
+        live_spans(&self.dead, self.vec.len()).map(move |span| &self.vec[span])
+    }
+
+    /// The parts of `vec` not covered by range tombstones. 
+    /// Unique-reference version.
+    fn live_slices_mut(&mut self) -> Vec<&mut [SVPair<K, V>]> {
+        let mut slices = Vec::with_capacity(self.dead.len() + 1);
+        let mut rest = &mut self.vec[..];
+        let mut offset = 0;
+
+        for span in live_spans(&self.dead, rest.len()) {
+            let (_, tail) = core::mem::take(&mut rest).split_at_mut(span.start - offset);
+            let (live, tail) = tail.split_at_mut(span.len());
+
+            slices.push(live);
+            rest = tail;
+            offset = span.end;
+        }
+
+        slices
+    }
+
+    /// Drops the entries covered by range tombstones.
+    fn purge(&mut self) {
+        for span in self.dead.drain(..).rev() {
+            self.vec.drain(span);
+        }
+    }
+
+    /// Removes a live entry shifting the range tombstones after it.
+    fn remove_at(&mut self, index: usize) -> SVPair<K, V> {
+        for span in &mut self.dead {
+            if span.start > index {
+                span.start -= 1;
+                span.end -= 1;
+            }
+        }
+
+        self.vec.remove(index)
+    }
+
+    /// Checks the key against the first and the last keys of the unit.
+    fn fences_contain<Q: Ord + ?Sized>(&self, key: &Q) -> bool where
+        K: core::borrow::Borrow<Q>
+    {
+        match (self.vec.first(), self.vec.last()) {
+            (Some(first), Some(last)) => {
+                first.0.borrow() <= key && key <= last.0.borrow()
+            }
+
+            _ => false,
+        }
+    }
+
+    /// Checks the range against the first and the last keys of the unit.
+    fn fences_overlap<Q, R>(&self, range: &R) -> bool where
+        K: core::borrow::Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: core::ops::RangeBounds<Q>,
+    {
+        use core::ops::Bound::*;
+
+        match (self.vec.first(), self.vec.last()) {
+            (Some(first), Some(last)) => {
+                let after_start = match range.start_bound() {
+                    Included(start) => last.0.borrow() >= start,
+                    Excluded(start) => last.0.borrow() > start,
+                    Unbounded => true,
+                };
+
+                let before_end = match range.end_bound() {
+                    Included(end) => first.0.borrow() <= end,
+                    Excluded(end) => first.0.borrow() < end,
+                    Unbounded => true,
+                };
+
+                after_start && before_end
+            }
+
+            _ => false,
+        }
+    }
+}
+
+impl<K: Ord, V, F: Filter<K>> SVMapUnit<K, V, F> {
+    fn from_vec(vec: Vec<SVPair<K, V>>, free_count: usize) -> Self {
+        let filter = F::build(vec.iter().map(|entry| &entry.0));
+
+        SVMapUnit { vec, filter, free_count, dead: Vec::new() }
+    }
+}
+
+impl<K: Ord, V, F: Filter<K>> Static for SVMapUnit<K, V, F> {
+    fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Drops the removed entries: the keys are unique, so they shadow 
+    /// nothing.
+    fn merge_with(mut self, mut other: Self) -> Self {
+        self.purge();
+        other.purge();
+
+        let mut vec = merge_vecs(self.vec, other.vec);
+
+        if self.free_count + other.free_count > 0 {
+            vec.retain(|entry| entry.1.is_some());
+        }
+
+        Self::from_vec(vec, 0)
+    }
+}
+
+impl<K, V, F: Default> Singleton for SVMapUnit<K, V, F> {
+    type Item = SVPair<K, V>;
+    
+    fn singleton(item: Self::Item) -> Self {
+        let free_count = item.1.is_none() as usize;
+
+        SVMapUnit { vec: vec![item], filter: F::default(), free_count, dead: Vec::new() }
+    }
+}
+
+impl<K: Ord, V, F: Default> Insert for SVMapUnit<K, V, F> {
+    /// Drops the filter: a buffer gets a new one when merged.
+    fn insert(&mut self, item: Self::Item) {
+        let index = self.vec.partition_point(|x| x <= &item);
+
+        self.free_count += item.1.is_none() as usize;
+        self.vec.insert(index, item);
+        self.filter = F::default();
+    }
+}
+
 impl<K: Ord, V> Ord for SVPair<K, V> {
     fn cmp(&self, other: &Self) -> core::cmp::Ordering {
         self.0.cmp(&other.0)
@@ -295,9 +663,9 @@ impl<K: Ord, V> SVMap<K, V> {
     /// Uses the [`Binary`](strategy::Binary) strategy.
     pub fn new() -> Self {
         SVMap {
-            dynamic: Dynamic::new(),
+            dynamic: Buffered::new(),
             len: 0,
-            free_count: 0,
+            policy: RebuildPolicy::default(),
         }
     }
 
@@ -314,15 +682,47 @@ impl<K: Ord, V> SVMap<K, V> {
     /// ```
     pub fn with_strategy<S: Strategy>() -> SVMap<K, V, S> {
         SVMap {
-            dynamic: Dynamic::new(),
+            dynamic: Buffered::new(),
+            len: 0,
+            policy: RebuildPolicy::default(),
+        }
+    }
+
+    /// Can be used as in 
+    ///
+    /// ```
+    /// # #[cfg(feature="sorted_vec")] {
+    /// use dynamization::sorted_vec::{ SVMap, Bloom };
+    ///
+    /// let svmap = SVMap::<String, i32>::with_filter::<Bloom<16>>();
+    /// # }
+    /// ```
+    ///
+    /// Other strategies can be combined with filters through
+    /// [`default`](Default::default): e.g. 
+    /// `SVMap::<String, i32, SkewBinary, Bloom10>::default()`.
+    pub fn with_filter<F: Filter<K>>() -> SVMap<K, V, strategy::Binary, F> {
+        SVMap {
+            dynamic: Buffered::new(),
+            len: 0,
+            policy: RebuildPolicy::default(),
+        }
+    }
+}
+
+
+impl<K: Ord, V, S: Strategy, F: Filter<K>> Default for SVMap<K, V, S, F> {
+    fn default() -> Self {
+        SVMap {
+            dynamic: Buffered::new(),
             len: 0,
-            free_count: 0,
+            policy: RebuildPolicy::default(),
         }
     }
 }
 
 
-impl<K: Ord, V, S: Strategy> SVMap<K, V, S> {
+impl<K: Ord, V, S: Strategy, F: Filter<K>> SVMap<K, V, S, F> {
     /// Returns the number of elements currently stored.
     pub fn len(&self) -> usize {
         self.len
@@ -333,56 +733,72 @@ impl<K: Ord, V, S: Strategy> SVMap<K, V, S> {
         self.len == 0
     }
 
-    fn search<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&SVPair<K, V>> where
-        K: core::borrow::Borrow<Q> 
+    /// Finds the entry with the key (possibly a tombstone).
+    ///
+    /// Returns the number of its unit in `units().rev()` and its index 
+    /// in the unit.
+    // The larger units are probed first: a present key is most likely there.
+    fn locate<Q: Ord + ?Sized>(&self, key: &Q) -> Option<(usize, usize)> where
+        K: core::borrow::Borrow<Q>,
+        F: Filter<Q>
     {
-        for unit in self.dynamic.units() {
-            let vec = &unit.vec;
+        for (unit_no, unit) in self.dynamic.units().rev().enumerate() {
+            if !unit.fences_contain(key) { continue; }
+            if !unit.filter.may_contain(key) { continue; }
 
-            if let Ok(index) = vec.binary_search_by(|entry| {
+            if let Ok(index) = unit.vec.binary_search_by(|entry| {
                 entry.0.borrow().cmp(key)
             }) {
-                // Safety: binary search returned Ok
-                return unsafe { Some(vec.get_unchecked(index)) }
+                // A removed copy: the key may be present in a newer unit.
+                if unit.is_dead(index) { continue; }
+
+                return Some((unit_no, index));
             }
         }
 
         None
     }
 
-    fn search_mut<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<&mut SVPair<K, V>> where
-        K: core::borrow::Borrow<Q> 
+    fn unit_mut(&mut self, unit_no: usize) -> Option<&mut SVMapUnit<K, V, F>> {
+        self.dynamic.units_mut().rev().nth(unit_no)
+    }
+
+    fn search<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&SVPair<K, V>> where
+        K: core::borrow::Borrow<Q>,
+        F: Filter<Q>
     {
-        for unit in self.dynamic.units_mut() {
-            let vec = &mut unit.vec;
+        let (unit_no, index) = self.locate(key)?;
 
-            if let Ok(index) = vec.binary_search_by(|entry| {
-                entry.0.borrow().cmp(key)
-            }) {
-                // Safety: binary search returned Ok
-                return unsafe { Some(vec.get_unchecked_mut(index)) }
-            }
-        }
+        self.dynamic.units().rev().nth(unit_no).map(|unit| &unit.vec[index])
+    }
 
-        None
+    fn search_mut<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<&mut SVPair<K, V>> where
+        K: core::borrow::Borrow<Q>,
+        F: Filter<Q>
+    {
+        let (unit_no, index) = self.locate(key)?;
+
+        self.unit_mut(unit_no).map(|unit| &mut unit.vec[index])
     }
     
     /// Searches for an item with a specified key. 
     ///
     /// Returns `true` if the item is found.
     pub fn contains_key<Q: Ord + ?Sized>(&self, key: &Q) -> bool where
-        K: core::borrow::Borrow<Q>
+        K: core::borrow::Borrow<Q>,
+        F: Filter<Q>
     {
-        self.search(key).is_some()
+        self.get(key).is_some()
     }
 
     /// Searches for an item with a specified key. 
     ///
     /// Returns a shared reference to the item found or `None`.
     pub fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&V> where
-        K: core::borrow::Borrow<Q>
+        K: core::borrow::Borrow<Q>,
+        F: Filter<Q>
     {
-        self.search(key).map(|entry| entry.1.as_ref()).flatten()
+        self.search(key).and_then(|entry| entry.1.as_ref())
     }
 
     /// Searches for an item with a specified key. 
@@ -390,143 +806,193 @@ impl<K: Ord, V, S: Strategy> SVMap<K, V, S> {
     /// Returns shared references to the key and the value stored or `None` if 
     /// the item has not been found.
     pub fn get_key_value<Q: Ord + ?Sized>(&self, key: &Q) -> Option<(&K, &V)> where
-        K: core::borrow::Borrow<Q>
+        K: core::borrow::Borrow<Q>,
+        F: Filter<Q>
     {
-        self.search(key).map(|entry| {
-            match &entry.1 {
-                Some(v) => { Some( (&entry.0, v) ) }
-                None => { None }
-            }
-        }).flatten()
+        self.search(key).and_then(|entry| {
+            entry.1.as_ref().map(|v| (&entry.0, v))
+        })
     }
 
     /// Searches for an item with a specified key. 
     ///
     /// Returns an exclusive reference to the item found or `None`.
     pub fn get_mut<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<&mut V> where
-        K: core::borrow::Borrow<Q>
+        K: core::borrow::Borrow<Q>,
+        F: Filter<Q>
     {
-        self.search_mut(key).map(|entry| entry.1.as_mut()).flatten()
+        self.search_mut(key).and_then(|entry| entry.1.as_mut())
+    }
+
+    /// Searches for an item with a specified key. 
+    ///
+    /// Returns a shared reference to the key and an exclusive reference 
+    /// to the value or `None` if the item has not been found. The key can't 
+    /// be changed in place: see [`replace_key`](SVMap::replace_key).
+    pub fn get_key_value_mut<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<(&K, &mut V)> where
+        K: core::borrow::Borrow<Q>,
+        F: Filter<Q>
+    {
+        self.search_mut(key).and_then(|entry| {
+            let SVPair(key, value) = entry;
+            let key: &K = key;
+
+            value.as_mut().map(move |value| (key, value))
+        })
     }
 
     /// Inserts a new key-value pair into the map.
     ///
     /// Returns the old value if it has been present. __Does not__ update 
-    /// the key in such a case!
+    /// the key in such a case (see 
+    /// [`insert_replace_key`](SVMap::insert_replace_key))!
     pub fn insert(&mut self, key: K, value: V) -> Option<V> {
-        if let Some(entry) = self.search_mut(&key) {
-            let result = core::mem::replace(&mut entry.1, Some(value));
+        match self.entry(key) {
+            Entry::Occupied(mut entry) => Some(entry.insert(value)),
 
-            if let None = result {
-                self.len += 1;
-                self.free_count -= 1;
+            Entry::Vacant(entry) => {
+                entry.insert(value);
+                None
             }
-
-            result
-        } else {
-            self.dynamic.insert(SVPair(key, Some(value)));
-            self.len += 1;
-            None
         }
     }
-   
 
-    const REBUILD_THRESHOLD: usize = 16;
-   
+
     /// Removes an item from the container.
     ///
     /// Returns the item removed or `None` if the item has not been found.
     pub fn remove<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<V> where
-        K: core::borrow::Borrow<Q>
+        K: core::borrow::Borrow<Q>,
+        F: Filter<Q>
     {
-        if let Some(entry) = self.search_mut(&key) {
-            let result = core::mem::replace(&mut entry.1, None);
-
-            if let Some(_) = result {
-                self.len -= 1;
-                self.free_count += 1;
-            }
+        let (unit_no, index) = self.locate(key)?;
+        let unit = self.unit_mut(unit_no)?;
+        let result = unit.vec[index].1.take();
 
-            if self.free_count > self.len && self.len > Self::REBUILD_THRESHOLD {
-                let mut tmp = SVMap::<K, V>::with_strategy::<S>();
-
-                core::mem::swap(self, &mut tmp);
+        if result.is_some() {
+            unit.free_count += 1;
+            self.len -= 1;
+        }
 
-                *self = tmp.into_iter().collect();
-            }
+        self.rebuild_if_sparse();
 
-            result
-        } else { None }
+        result
     }
-    
-   
+
     /// Removes all elements from the map.
     pub fn clear(&mut self) {
         self.dynamic.clear();
         self.len = 0;
-        self.free_count = 0;
     }
-}
 
-impl<K: Ord, V, S: Strategy> core::iter::FromIterator<(K, V)> for SVMap<K, V, S> {
-    fn from_iter<I: IntoIterator<Item=(K, V)>>(iter: I) -> Self {
-        let sv = iter
-            .into_iter()
-            .map(|(k,v)| SVPair(k, Some(v)))
-            .collect::<SortedVec<_>>();
+    /// Moves all the elements of `other` into `self` leaving `other` empty.
+    ///
+    /// The values from `other` replace the values with the same keys 
+    /// (as [`BTreeMap::append`](alloc::collections::BTreeMap::append) does).
+    /// See [`append_with`](SVMap::append_with) for other policies.
+    pub fn append<S2: Strategy>(&mut self, other: &mut SVMap<K, V, S2, F>) {
+        self.append_with(other, |_, _, right| right);
+    }
 
-        let mut dynamic = Dynamic::new();
-        let len = sv.len();
-        dynamic.add_unit(sv);
+    /// Moves all the elements of `other` into `self` leaving `other` empty.
+    ///
+    /// The value for a key present in both maps is set to 
+    /// `combine(key, left, right)` where `left` is from `self` and `right` 
+    /// is from `other`: e.g. `|_, left, _| left` keeps the values of `self`.
+    ///
+    /// The units of `other` are added to `self` as they are: only 
+    /// the duplicate keys are searched for and removed from them.
+    pub fn append_with<S2, C>(&mut self, other: &mut SVMap<K, V, S2, F>, mut combine: C) where
+        S2: Strategy,
+        C: FnMut(&K, V, V) -> V,
+    {
+        let units = core::mem::take(&mut other.dynamic);
+        other.len = 0;
 
-        Self {
-            dynamic,
-            len,
-            free_count: 0,
-        }
-    }
-}
+        for mut unit in units {
+            unit.purge();
 
+            let mut vec = Vec::with_capacity(unit.vec.len());
 
-/// Iterator over key-value pairs for [`SVMap`].
-///
-/// __Does not__ sort the values by key.
-// FIXME: sort entries by key! (or better add a corresponding method)
-pub struct SVMapKV<K, V> {
-    unit_iter: DynamicIntoIter<SortedVec<SVPair<K, V>>>,
-    opt_kv_iter: Option<alloc::vec::IntoIter<SVPair<K, V>>>,
-}
-
-impl<K, V> Iterator for SVMapKV<K, V> {
-    type Item = (K, V);
-
-    fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            if let Some(kv_iter) = &mut self.opt_kv_iter {
-                while let Some(sv_pair) = kv_iter.next() {
-                    if let Some(value) = sv_pair.1 {
-                        return Some( (sv_pair.0, value) );
+            for SVPair(key, value) in unit.vec {
+                let right = match value {
+                    Some(right) => right,
+                    None => { continue; }
+                };
+
+                match self.locate(&key) {
+                    None => {
+                        vec.push(SVPair(key, Some(right)));
+                    }
+
+                    Some((unit_no, index)) => {
+                        let unit = match self.unit_mut(unit_no) {
+                            Some(unit) => unit,
+                            None => { continue; }
+                        };
+
+                        let entry = &mut unit.vec[index];
+
+                        match entry.1.take() {
+                            Some(left) => {
+                                entry.1 = Some(combine(&entry.0, left, right));
+                            }
+
+                            None => {
+                                entry.1 = Some(right);
+                                unit.free_count -= 1;
+                                self.len += 1;
+                            }
+                        }
                     }
                 }
-            } // kv_iter is empty or absent
-            
-            self.opt_kv_iter = 
-                self.unit_iter.next().map(|sv| sv.vec.into_iter());
-            
-            if let None = self.opt_kv_iter { return None; }
+            }
+
+            if !vec.is_empty() {
+                self.len += vec.len();
+                self.dynamic.add_unit(SVMapUnit::from_vec(vec, 0));
+            }
         }
     }
 }
 
+/// Sorts the pairs by key keeping the last one of the equal keys.
+fn sorted_pairs<K: Ord, V, I: IntoIterator<Item=(K, V)>>(iter: I) -> Vec<SVPair<K, V>> {
+    let mut sv = iter
+        .into_iter()
+        .map(|(k,v)| SVPair(k, Some(v)))
+        .collect::<SortedVec<_>>();
+
+    // The sort is stable: the last one of equal keys stays the last.
+    sv.vec.dedup_by(|later, earlier| {
+        let equal = later.0 == earlier.0;
+
+        if equal {
+            core::mem::swap(later, earlier);
+        }
+
+        equal
+    });
+
+    sv.vec
+}
+
+impl<K: Ord, V, S: Strategy, F: Filter<K>> core::iter::FromIterator<(K, V)> for SVMap<K, V, S, F> {
+    /// The later pairs overwrite the earlier ones with the same key.
+    fn from_iter<I: IntoIterator<Item=(K, V)>>(iter: I) -> Self {
+        let vec = sorted_pairs(iter);
+
+        let mut dynamic = Buffered::new();
+        let len = vec.len();
 
-impl<K: Ord, V, S> core::iter::IntoIterator for SVMap<K, V, S> {
-    type Item = (K, V);
-    type IntoIter = SVMapKV<K, V>;
+        if len > 0 {
+            dynamic.add_unit(SVMapUnit::from_vec(vec, 0));
+        }
 
-    fn into_iter(self) -> Self::IntoIter {
-        SVMapKV {
-            unit_iter: self.dynamic.into_iter(),
-            opt_kv_iter: None,
+        Self {
+            dynamic,
+            len,
+            policy: RebuildPolicy::default(),
         }
     }
 }
@@ -0,0 +1,124 @@
+//! Batched lookups and insertions in [`SVMap`].
+//!
+//! A sorted batch of keys is matched against each unit by a single
+//! forward scan. The scan gallops: it probes `1, 2, 4, ...` entries ahead
+//! and then searches between the last two probes, so a batch of `M` keys
+//! takes `O(M log(N / M))` comparisons per unit instead of `O(M log N)`.
+
+use crate::*;
+use super::{ SVMap, SVMapUnit, SVPair, Filter, live_spans };
+use core::borrow::Borrow;
+
+
+/// The first index not less than `start` where `less` is `false`.
+///
+/// `less` must be monotonic over `slice`.
+fn gallop<T, L: Fn(&T) -> bool>(slice: &[T], start: usize, less: L) -> usize {
+    let mut low = start;
+    let mut step = 1;
+
+    while low + step <= slice.len() && less(&slice[low + step - 1]) {
+        low += step;
+        step *= 2;
+    }
+
+    let high = core::cmp::min(low + step, slice.len());
+
+    low + slice[low..high].partition_point(less)
+}
+
+
+impl<K: Ord, V, S: Strategy, F: Filter<K>> SVMap<K, V, S, F> {
+    /// Searches for all the keys at once.
+    ///
+    /// Returns shared references to the values found in the order
+    /// of `keys`. Much faster than repeated [`get`](SVMap::get) for large
+    /// batches: the keys are sorted and each unit is scanned once.
+    ///
+    /// ```
+    /// # #[cfg(feature="sorted_vec")] {
+    /// use dynamization::sorted_vec::SVMap;
+    ///
+    /// let map: SVMap<_, _> = (0..100).map(|x| (x * 2, x)).collect();
+    ///
+    /// assert_eq!(map.get_many(&[10, 3, 0]), vec![Some(&5), None, Some(&0)]);
+    /// # }
+    /// ```
+    pub fn get_many<Q: Ord>(&self, keys: &[Q]) -> Vec<Option<&V>> where
+        K: Borrow<Q>,
+        F: Filter<Q>
+    {
+        let mut order: Vec<usize> = (0..keys.len()).collect();
+        order.sort_unstable_by(|&i, &j| keys[i].cmp(&keys[j]));
+
+        let mut result = alloc::vec![None; keys.len()];
+
+        for unit in self.dynamic.units() {
+            for slice in unit.live_slices() {
+                let mut position = 0;
+
+                for &i in &order {
+                    let key = &keys[i];
+
+                    if !unit.filter.may_contain(key) { continue; }
+
+                    position = gallop(slice, position, |entry| entry.0.borrow() < key);
+
+                    if position == slice.len() { break; }
+
+                    let entry = &slice[position];
+
+                    if entry.0.borrow() == key {
+                        result[i] = entry.1.as_ref();
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Adds a batch of pairs sorted by unique keys.
+    ///
+    /// The values for the keys already present replace the stored ones
+    /// in place; the rest of the batch becomes a new unit.
+    pub(super) fn extend_sorted(&mut self, mut batch: Vec<SVPair<K, V>>) {
+        for unit in self.dynamic.units_mut() {
+            let SVMapUnit { vec, filter, free_count, dead } = unit;
+
+            for span in live_spans(dead, vec.len()) {
+                let slice = &mut vec[span];
+                let mut position = 0;
+
+                for entry in &mut batch {
+                    // Already stored in another unit.
+                    if entry.1.is_none() { continue; }
+
+                    if !filter.may_contain(&entry.0) { continue; }
+
+                    position = gallop(slice, position, |stored| stored.0 < entry.0);
+
+                    if position == slice.len() { break; }
+
+                    let stored = &mut slice[position];
+
+                    if stored.0 == entry.0 {
+                        if stored.1.is_none() {
+                            *free_count -= 1;
+                            self.len += 1;
+                        }
+
+                        stored.1 = entry.1.take();
+                    }
+                }
+            }
+        }
+
+        batch.retain(|entry| entry.1.is_some());
+
+        if !batch.is_empty() {
+            self.len += batch.len();
+            self.dynamic.add_unit(SVMapUnit::from_vec(batch, 0));
+        }
+    }
+}
@@ -0,0 +1,409 @@
+//! Cache-oblivious lookahead array.
+//!
+//! [`SVCola`] is a multiset and [`SVColaMap`] is an associative array
+//! built on it. [`SVMap`](super::SVMap) and the other containers still
+//! search each unit independently.
+
+use crate::*;
+use super::{ SortedVec, SVPair, RebuildPolicy, sorted_pairs };
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+
+
+/// An insert-only sorted multiset with fractional cascading between 
+/// the units.
+///
+/// Supports insertions and point lookups only: see [`SVColaMap`] for
+/// removals.
+///
+/// Each unit (level) of the [`Binary`](strategy::Binary) dynamization gets
+/// a lookahead array: its own items merged with every second entry of
+/// the lookahead array of the next nonempty level. So a lookup does
+/// one full binary search in the smallest level and then `O(1)` work per
+/// level instead of an independent binary search in every unit.
+///
+/// After each merge the lookahead arrays of the merged level and of all
+/// the smaller ones are rebuilt in time linear in their total size.
+#[derive(Clone, Debug)]
+pub struct SVCola<T> {
+    dynamic: Dynamic<SortedVec<T>, strategy::Binary>,
+    levels: Vec<Level>,
+    len: usize,
+}
+
+/// A lookahead array for a single level.
+///
+/// Every entry refers to an item stored in this level (a native entry)
+/// or in one of the next levels (a sampled entry).
+#[derive(Clone, Debug, Default)]
+struct Level {
+    /// Unit sizes are checked to find the levels changed by a merge.
+    unit_len: usize,
+
+    /// `(slot, index)` of the item.
+    entries: Vec<(usize, usize)>,
+
+    /// `natives[p]` is the number of the native entries in `entries[..p]`.
+    natives: Vec<usize>,
+}
+
+
+impl<T: Ord> SVCola<T> {
+    /// An empty container.
+    pub fn new() -> Self {
+        SVCola {
+            dynamic: Dynamic::new(),
+            levels: Vec::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of elements currently stored.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `self.len() == 0`.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts a new item into the container.
+    ///
+    /// Equal items are stored separately.
+    pub fn insert(&mut self, item: T) {
+        self.dynamic.insert(item);
+        self.len += 1;
+        self.rebuild();
+    }
+
+    /// Searches for an item equal to `key`.
+    pub fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&T> where
+        T: Borrow<Q>
+    {
+        let location = self.locate_by(|x| x.borrow().cmp(key))?;
+
+        Some(self.item(location))
+    }
+
+    fn item(&self, (slot, index): (usize, usize)) -> &T {
+        &self.dynamic.slots()[slot].as_ref().unwrap().vec[index]
+    }
+
+    /// The item must keep its place in the order.
+    fn item_mut(&mut self, (slot, index): (usize, usize)) -> &mut T {
+        &mut self.dynamic.slots_mut()[slot].as_mut().unwrap().vec[index]
+    }
+
+    /// Searches for an item for which `cmp` returns `Equal`: `cmp` orders
+    /// the items relative to the key.
+    ///
+    /// Returns the slot of the unit and the index in the unit.
+    fn locate_by<C: Fn(&T) -> Ordering>(&self, cmp: C) -> Option<(usize, usize)> {
+        let slots = self.dynamic.slots();
+        let item = |(slot, index): (usize, usize)| -> &T {
+            &slots[slot].as_ref().unwrap().vec[index]
+        };
+
+        let mut slot = self.next_level(0)?;
+        let mut level = &self.levels[slot];
+
+        let mut p = level.entries.partition_point(|&entry| {
+            cmp(item(entry)) == Ordering::Less
+        });
+
+        loop {
+            let native = level.natives[p];
+            let vec = &slots[slot].as_ref()?.vec;
+
+            if let Some(x) = vec.get(native) {
+                if cmp(x) == Ordering::Equal { return Some((slot, native)); }
+            }
+
+            slot = self.next_level(slot + 1)?;
+            level = &self.levels[slot];
+
+            // Every second entry of the next level has been sampled:
+            // the ones preceding `p` are less than `key`, the next
+            // one (if present) is not less.
+            let mut q = 2 * (p - native);
+
+            if let Some(&entry) = level.entries.get(q) {
+                if cmp(item(entry)) == Ordering::Less { q += 1; }
+            }
+
+            p = q;
+        }
+    }
+
+    /// Returns `true` if the container has an item equal to `key`.
+    pub fn contains<Q: Ord + ?Sized>(&self, key: &Q) -> bool where
+        T: Borrow<Q>
+    {
+        self.get(key).is_some()
+    }
+
+    /// Removes all elements.
+    pub fn clear(&mut self) {
+        self.dynamic.clear();
+        self.levels.clear();
+        self.len = 0;
+    }
+
+    /// The first nonempty level starting from `slot`.
+    fn next_level(&self, slot: usize) -> Option<usize> {
+        (slot..self.levels.len()).find(|&i| self.levels[i].unit_len > 0)
+    }
+
+    /// Rebuilds the lookahead arrays for all the levels up to
+    /// the last changed one.
+    fn rebuild(&mut self) {
+        let slots = self.dynamic.slots();
+        let unit_len = |slot: usize| slots[slot].as_ref().map_or(0, |u| u.vec.len());
+
+        self.levels.resize_with(slots.len(), Default::default);
+
+        let top = match (0..slots.len()).rev().find(|&slot| {
+            self.levels[slot].unit_len != unit_len(slot)
+        }) {
+            None => { return; }
+            Some(top) => top,
+        };
+
+        let mut next: Option<usize> = self.next_level(top + 1);
+
+        for slot in (0..=top).rev() {
+            let len = unit_len(slot);
+
+            if len == 0 {
+                self.levels[slot] = Level::default();
+                continue;
+            }
+
+            let sampled: &[(usize, usize)] = match next {
+                None => &[],
+                Some(next) => &self.levels[next].entries,
+            };
+
+            let item = |(slot, index): (usize, usize)| -> &T {
+                &slots[slot].as_ref().unwrap().vec[index]
+            };
+
+            let capacity = len + sampled.len() / 2;
+            let mut entries = Vec::with_capacity(capacity);
+            let mut natives = Vec::with_capacity(capacity + 1);
+
+            let mut samples = sampled.iter().skip(1).step_by(2).peekable();
+            let mut native = 0;
+
+            natives.push(0);
+
+            while native < len || samples.peek().is_some() {
+                let take_native = match samples.peek() {
+                    None => true,
+                    Some(&&sample) => {
+                        native < len && item((slot, native)) <= item(sample)
+                    }
+                };
+
+                if take_native {
+                    entries.push((slot, native));
+                    native += 1;
+                } else {
+                    entries.push(*samples.next().unwrap());
+                }
+
+                natives.push(native);
+            }
+
+            self.levels[slot] = Level { unit_len: len, entries, natives };
+            next = Some(slot);
+        }
+    }
+}
+
+impl<T: Ord> Default for SVCola<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> SVCola<T> {
+    /// A container with a single level.
+    fn from_sorted(sv: SortedVec<T>) -> Self {
+        let mut cola = Self::new();
+        cola.len = sv.len();
+
+        if cola.len > 0 {
+            cola.dynamic.add_unit(sv);
+            cola.rebuild();
+        }
+
+        cola
+    }
+
+    /// Takes the items out in sorted order.
+    fn into_sorted(self) -> SortedVec<T> {
+        self.dynamic.try_collect().unwrap_or_else(|| SortedVec { vec: Vec::new() })
+    }
+}
+
+impl<T: Ord> core::iter::FromIterator<T> for SVCola<T> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        Self::from_sorted(iter.into_iter().collect())
+    }
+}
+
+
+/// An associative array with fractional cascading between the units:
+/// see [`SVCola`].
+///
+/// A lookup does one full binary search and then `O(1)` work per level.
+/// An insertion of a present key and a removal update the value in place:
+/// a removal leaves a tombstone. The map is rebuilt into a single level
+/// when the tombstones outnumber the items (as with the default
+/// [`RebuildPolicy`]).
+///
+/// ```
+/// # #[cfg(feature="sorted_vec")] {
+/// use dynamization::sorted_vec::SVColaMap;
+///
+/// let mut map = SVColaMap::new();
+///
+/// map.insert("a", 1);
+/// map.insert("b", 2);
+/// map.remove("a");
+///
+/// assert_eq!(map.get("a"), None);
+/// assert_eq!(map.get("b"), Some(&2));
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct SVColaMap<K, V> {
+    cola: SVCola<SVPair<K, V>>,
+    len: usize,
+}
+
+impl<K: Ord, V> SVColaMap<K, V> {
+    /// An empty map.
+    pub fn new() -> Self {
+        SVColaMap {
+            cola: SVCola::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of elements currently stored.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `self.len() == 0`.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Finds the entry with the key (possibly a tombstone).
+    fn locate<Q: Ord + ?Sized>(&self, key: &Q) -> Option<(usize, usize)> where
+        K: Borrow<Q>
+    {
+        self.cola.locate_by(|entry| entry.0.borrow().cmp(key))
+    }
+
+    /// Returns `true` if the key is present.
+    pub fn contains_key<Q: Ord + ?Sized>(&self, key: &Q) -> bool where
+        K: Borrow<Q>
+    {
+        self.get(key).is_some()
+    }
+
+    /// Returns a shared reference to the value or `None`.
+    pub fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&V> where
+        K: Borrow<Q>
+    {
+        let location = self.locate(key)?;
+
+        self.cola.item(location).1.as_ref()
+    }
+
+    /// Returns an exclusive reference to the value or `None`.
+    pub fn get_mut<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<&mut V> where
+        K: Borrow<Q>
+    {
+        let location = self.locate(key)?;
+
+        self.cola.item_mut(location).1.as_mut()
+    }
+
+    /// Inserts a new key-value pair into the map.
+    ///
+    /// Returns the old value if it has been present.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(location) = self.locate(&key) {
+            let result = self.cola.item_mut(location).1.replace(value);
+
+            if result.is_none() {
+                self.len += 1;
+            }
+
+            return result;
+        }
+
+        self.cola.insert(SVPair(key, Some(value)));
+        self.len += 1;
+
+        None
+    }
+
+    /// Removes the value leaving a tombstone with the key.
+    ///
+    /// Returns the value removed or `None` if the key has not been found.
+    pub fn remove<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<V> where
+        K: Borrow<Q>
+    {
+        let location = self.locate(key)?;
+        let result = self.cola.item_mut(location).1.take();
+
+        if result.is_some() {
+            self.len -= 1;
+            self.rebuild_if_sparse();
+        }
+
+        result
+    }
+
+    /// Removes all elements from the map.
+    pub fn clear(&mut self) {
+        self.cola.clear();
+        self.len = 0;
+    }
+
+    /// Drops the tombstones if they outnumber the items.
+    fn rebuild_if_sparse(&mut self) {
+        if RebuildPolicy::default().rebuilds(self.len, self.cola.len()) {
+            let mut sv = core::mem::take(&mut self.cola).into_sorted();
+            sv.vec.retain(|entry| entry.1.is_some());
+
+            self.cola = SVCola::from_sorted(sv);
+        }
+    }
+}
+
+impl<K: Ord, V> Default for SVColaMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V> core::iter::FromIterator<(K, V)> for SVColaMap<K, V> {
+    /// The later pairs overwrite the earlier ones with the same key.
+    fn from_iter<I: IntoIterator<Item=(K, V)>>(iter: I) -> Self {
+        let vec = sorted_pairs(iter);
+        let len = vec.len();
+
+        SVColaMap {
+            cola: SVCola::from_sorted(SortedVec { vec }),
+            len,
+        }
+    }
+}
@@ -0,0 +1,504 @@
+//! Containers ordered by a comparator instead of `Ord`.
+//!
+//! A comparator is a type implementing [`Compare`]. [`Ordered`] wraps
+//! an item to order it by the comparator: the variants below store
+//! the wrapped items in the `Ord`-based containers and take and return
+//! the plain ones.
+//!
+//! The comparators are stateless: `Ord::cmp` has no room for a comparator
+//! value, so the order can depend only on the comparator type. An order
+//! known only at runtime needs a key wrapper carrying it instead.
+
+use crate::*;
+use super::{
+    SortedVec, SVQueue, SVQueuePeekMut,
+    SVMap, SVMapIter, SVMapIterMut, SVMapKeys, SVMapValues, SVMapValuesMut,
+    SVMapRange, SVMapRangeMut, Entry,
+};
+use core::cmp::Ordering;
+use core::marker::PhantomData;
+use core::ops::{ Bound, RangeBounds };
+
+
+/// A total order on `T`.
+///
+/// ```
+/// # #[cfg(feature="sorted_vec")] {
+/// use dynamization::sorted_vec::{ Compare, SVQueueBy };
+/// use std::cmp::Ordering;
+///
+/// // Orders the tasks by priority only.
+/// struct ByPriority;
+///
+/// impl Compare<(u32, &str)> for ByPriority {
+///     fn compare(a: &(u32, &str), b: &(u32, &str)) -> Ordering {
+///         a.0.cmp(&b.0)
+///     }
+/// }
+///
+/// let mut tasks = SVQueueBy::<_, ByPriority>::new();
+///
+/// tasks.push((1, "write"));
+/// tasks.push((3, "review"));
+/// tasks.push((2, "test"));
+///
+/// assert_eq!(tasks.pop(), Some((3, "review")));
+/// # }
+/// ```
+pub trait Compare<T: ?Sized> {
+    /// Compares two items.
+    fn compare(a: &T, b: &T) -> Ordering;
+}
+
+
+/// The order given by `Ord`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Natural;
+
+impl<T: Ord + ?Sized> Compare<T> for Natural {
+    fn compare(a: &T, b: &T) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+
+/// The reverse of the order given by `Ord`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Reversed;
+
+impl<T: Ord + ?Sized> Compare<T> for Reversed {
+    fn compare(a: &T, b: &T) -> Ordering {
+        b.cmp(a)
+    }
+}
+
+
+/// An item ordered by the comparator `C`.
+#[repr(transparent)]
+pub struct Ordered<T, C> {
+    item: T,
+    comparator: PhantomData<C>,
+}
+
+impl<T, C> Ordered<T, C> {
+    /// Wraps the item.
+    pub fn new(item: T) -> Self {
+        Ordered { item, comparator: PhantomData }
+    }
+
+    /// Wraps a reference to the item.
+    pub fn from_ref(item: &T) -> &Self {
+        // Sound: the layouts are the same due to `repr(transparent)`.
+        unsafe { &*(item as *const T as *const Self) }
+    }
+
+    /// The item.
+    pub fn get(&self) -> &T {
+        &self.item
+    }
+
+    /// Unwraps the item.
+    pub fn into_inner(self) -> T {
+        self.item
+    }
+}
+
+impl<T: Clone, C> Clone for Ordered<T, C> {
+    fn clone(&self) -> Self {
+        Ordered::new(self.item.clone())
+    }
+}
+
+impl<T: core::fmt::Debug, C> core::fmt::Debug for Ordered<T, C> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.item.fmt(f)
+    }
+}
+
+impl<T, C: Compare<T>> PartialEq for Ordered<T, C> {
+    fn eq(&self, other: &Self) -> bool {
+        C::compare(&self.item, &other.item) == Ordering::Equal
+    }
+}
+
+impl<T, C: Compare<T>> Eq for Ordered<T, C> {}
+
+impl<T, C: Compare<T>> PartialOrd for Ordered<T, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T, C: Compare<T>> Ord for Ordered<T, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        C::compare(&self.item, &other.item)
+    }
+}
+
+
+/// A [`SortedVec`] ordered by the comparator `C`.
+pub type SortedVecBy<T, C> = SortedVec<Ordered<T, C>>;
+
+
+/// A max-priority queue ordered by the comparator `C`: see [`SVQueue`].
+pub struct SVQueueBy<T, C, S = strategy::Binary> {
+    queue: SVQueue<Ordered<T, C>, S>,
+}
+
+impl<T: Clone, C, S: Clone> Clone for SVQueueBy<T, C, S> {
+    fn clone(&self) -> Self {
+        SVQueueBy { queue: self.queue.clone() }
+    }
+}
+
+impl<T: core::fmt::Debug, C, S: core::fmt::Debug> core::fmt::Debug for SVQueueBy<T, C, S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SVQueueBy").field("queue", &self.queue).finish()
+    }
+}
+
+/// A min-priority queue: [`pop`](SVQueueBy::pop) removes the current
+/// minimum.
+///
+/// ```
+/// # #[cfg(feature="sorted_vec")] {
+/// use dynamization::sorted_vec::SVMinQueue;
+///
+/// let mut pqueue = SVMinQueue::new();
+///
+/// for x in [3, 1, 2] {
+///     pqueue.push(x);
+/// }
+///
+/// assert_eq!(pqueue.pop(), Some(1));
+/// assert_eq!(pqueue.peek(), Some(&2));
+/// # }
+/// ```
+pub type SVMinQueue<T, S = strategy::Binary> = SVQueueBy<T, Reversed, S>;
+
+impl<T, C: Compare<T>> SVQueueBy<T, C> {
+    /// Uses the [`Binary`](strategy::Binary) strategy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses a specified strategy.
+    pub fn with_strategy<S: Strategy>() -> SVQueueBy<T, C, S> {
+        SVQueueBy::default()
+    }
+}
+
+impl<T, C: Compare<T>, S: Strategy> Default for SVQueueBy<T, C, S> {
+    fn default() -> Self {
+        SVQueueBy { queue: SVQueue::default() }
+    }
+}
+
+impl<T, C: Compare<T>, S: Strategy> SVQueueBy<T, C, S> {
+    /// Returns the number of elements currently stored.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns `self.len() == 0`.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Inserts a new item into the container.
+    pub fn push(&mut self, item: T) {
+        self.queue.push(Ordered::new(item));
+    }
+
+    /// Returns the current maximum by `C`.
+    pub fn peek(&self) -> Option<&T> {
+        self.queue.peek().map(Ordered::get)
+    }
+
+    /// Exclusively returns the current maximum by `C`: see
+    /// [`SVQueue::peek_mut`].
+    pub fn peek_mut(&mut self) -> Option<SVQueueByPeekMut<'_, T, C>> {
+        self.queue.peek_mut().map(|guard| SVQueueByPeekMut { guard })
+    }
+
+    /// Removes the current maximum by `C` from the container.
+    pub fn pop(&mut self) -> Option<T> {
+        self.queue.pop().map(Ordered::into_inner)
+    }
+}
+
+/// A guard returned by [`SVQueueBy::peek_mut`]: see [`SVQueuePeekMut`].
+pub struct SVQueueByPeekMut<'a, T, C: Compare<T>> {
+    guard: SVQueuePeekMut<'a, Ordered<T, C>>,
+}
+
+impl<'a, T: core::fmt::Debug, C: Compare<T>> core::fmt::Debug for SVQueueByPeekMut<'a, T, C> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SVQueueByPeekMut").field("guard", &self.guard).finish()
+    }
+}
+
+impl<'a, T, C: Compare<T>> SVQueueByPeekMut<'a, T, C> {
+    /// Removes the peeked item from the queue.
+    pub fn pop(this: Self) -> T {
+        SVQueuePeekMut::pop(this.guard).into_inner()
+    }
+}
+
+impl<'a, T, C: Compare<T>> core::ops::Deref for SVQueueByPeekMut<'a, T, C> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.guard.get()
+    }
+}
+
+impl<'a, T, C: Compare<T>> core::ops::DerefMut for SVQueueByPeekMut<'a, T, C> {
+    fn deref_mut(&mut self) -> &mut T {
+        let ordered: &mut Ordered<T, C> = &mut self.guard;
+
+        &mut ordered.item
+    }
+}
+
+
+/// An associative array ordered by the comparator `C`: see [`SVMap`].
+///
+/// ```
+/// # #[cfg(feature="sorted_vec")] {
+/// use dynamization::sorted_vec::{ SVMapBy, Reversed };
+///
+/// let mut map = SVMapBy::<_, _, Reversed>::new();
+///
+/// map.insert(1, 'a');
+/// map.insert(2, 'b');
+///
+/// assert_eq!(map.get(&1), Some(&'a'));
+/// assert!(map.iter().eq(vec![(&2, &'b'), (&1, &'a')]));
+/// # }
+/// ```
+pub struct SVMapBy<K, V, C, S = strategy::Binary> {
+    map: SVMap<Ordered<K, C>, V, S>,
+}
+
+/// Iterator over `(&key, &value)` pairs of an [`SVMapBy`] sorted by `C`.
+pub type SVMapByIter<'a, K, V, C> = core::iter::Map<
+    SVMapIter<'a, Ordered<K, C>, V>,
+    fn((&'a Ordered<K, C>, &'a V)) -> (&'a K, &'a V),
+>;
+
+/// Iterator over `(&key, &mut value)` pairs of an [`SVMapBy`] sorted by `C`.
+pub type SVMapByIterMut<'a, K, V, C> = core::iter::Map<
+    SVMapIterMut<'a, Ordered<K, C>, V>,
+    fn((&'a Ordered<K, C>, &'a mut V)) -> (&'a K, &'a mut V),
+>;
+
+/// Iterator over the keys of an [`SVMapBy`] sorted by `C`.
+pub type SVMapByKeys<'a, K, V, C> = core::iter::Map<
+    SVMapKeys<'a, Ordered<K, C>, V>,
+    fn(&'a Ordered<K, C>) -> &'a K,
+>;
+
+/// Iterator over the values of an [`SVMapBy`] sorted by key.
+pub type SVMapByValues<'a, K, V, C> = SVMapValues<'a, Ordered<K, C>, V>;
+
+/// Iterator over mutable references to the values of an [`SVMapBy`]
+/// sorted by key.
+pub type SVMapByValuesMut<'a, K, V, C> = SVMapValuesMut<'a, Ordered<K, C>, V>;
+
+/// Iterator over a key range of an [`SVMapBy`]. Yields `(&key, &value)` pairs.
+pub type SVMapByRange<'a, K, V, C> = core::iter::Map<
+    SVMapRange<'a, Ordered<K, C>, V>,
+    fn((&'a Ordered<K, C>, &'a V)) -> (&'a K, &'a V),
+>;
+
+/// Iterator over a key range of an [`SVMapBy`]. Yields `(&key, &mut value)` pairs.
+pub type SVMapByRangeMut<'a, K, V, C> = core::iter::Map<
+    SVMapRangeMut<'a, Ordered<K, C>, V>,
+    fn((&'a Ordered<K, C>, &'a mut V)) -> (&'a K, &'a mut V),
+>;
+
+/// Wraps a bound of a key range.
+fn ordered_bound<K, C>(bound: Bound<&K>) -> Bound<&Ordered<K, C>> {
+    match bound {
+        Bound::Included(key) => Bound::Included(Ordered::from_ref(key)),
+        Bound::Excluded(key) => Bound::Excluded(Ordered::from_ref(key)),
+        Bound::Unbounded => Bound::Unbounded,
+    }
+}
+
+impl<K, V, C: Compare<K>> SVMapBy<K, V, C> {
+    /// Uses the [`Binary`](strategy::Binary) strategy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses a specified strategy.
+    pub fn with_strategy<S: Strategy>() -> SVMapBy<K, V, C, S> {
+        SVMapBy::default()
+    }
+}
+
+impl<K: Clone, V: Clone, C, S: Clone> Clone for SVMapBy<K, V, C, S> {
+    fn clone(&self) -> Self {
+        SVMapBy { map: self.map.clone() }
+    }
+}
+
+impl<K, V, C, S> core::fmt::Debug for SVMapBy<K, V, C, S> where
+    K: core::fmt::Debug,
+    V: core::fmt::Debug,
+    C: Compare<K>,
+    S: Strategy,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.map.fmt(f)
+    }
+}
+
+impl<K, V, C: Compare<K>, S: Strategy> Default for SVMapBy<K, V, C, S> {
+    fn default() -> Self {
+        SVMapBy { map: SVMap::default() }
+    }
+}
+
+impl<K, V, C: Compare<K>, S: Strategy> SVMapBy<K, V, C, S> {
+    /// Returns the number of elements currently stored.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `self.len() == 0`.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns `true` if the key is present.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.map.contains_key(Ordered::from_ref(key))
+    }
+
+    /// Returns a shared reference to the value or `None`.
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.map.get(Ordered::from_ref(key))
+    }
+
+    /// Returns shared references to the key and the value stored or `None`.
+    pub fn get_key_value(&self, key: &K) -> Option<(&K, &V)> {
+        self.map.get_key_value(Ordered::from_ref(key)).map(|(key, value)| (key.get(), value))
+    }
+
+    /// Returns an exclusive reference to the value or `None`.
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.map.get_mut(Ordered::from_ref(key))
+    }
+
+    /// Inserts a new key-value pair into the map.
+    ///
+    /// Returns the old value if it has been present (see [`SVMap::insert`]).
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.map.insert(Ordered::new(key), value)
+    }
+
+    /// Removes an item from the container.
+    ///
+    /// Returns the item removed or `None` if the item has not been found.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.map.remove(Ordered::from_ref(key))
+    }
+
+    /// Gets the entry for the key for in-place manipulation: see
+    /// [`SVMap::entry`].
+    ///
+    /// The entry holds the key [wrapped](Ordered).
+    pub fn entry(&mut self, key: K) -> Entry<'_, Ordered<K, C>, V, S> {
+        self.map.entry(Ordered::new(key))
+    }
+
+    /// Removes the pairs with the keys in `range` (ordered by `C`). Returns
+    /// the number of the pairs removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics in the same cases as [`range`](SVMapBy::range).
+    pub fn remove_range<R: RangeBounds<K>>(&mut self, range: R) -> usize {
+        let bounds = (ordered_bound(range.start_bound()), ordered_bound(range.end_bound()));
+
+        self.map.remove_range(bounds)
+    }
+
+    /// Removes and returns the first pair by `C`.
+    pub fn pop_first(&mut self) -> Option<(K, V)> where K: Clone {
+        self.map.pop_first().map(|(key, value)| (key.into_inner(), value))
+    }
+
+    /// Removes and returns the last pair by `C`.
+    pub fn pop_last(&mut self) -> Option<(K, V)> where K: Clone {
+        self.map.pop_last().map(|(key, value)| (key.into_inner(), value))
+    }
+
+    /// Removes all elements from the map.
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// Returns the first pair by `C`.
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.map.first_key_value().map(|(key, value)| (key.get(), value))
+    }
+
+    /// Returns the last pair by `C`.
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.map.last_key_value().map(|(key, value)| (key.get(), value))
+    }
+
+    /// Iterator over `(&key, &value)` pairs sorted by `C`.
+    pub fn iter(&self) -> SVMapByIter<'_, K, V, C> {
+        self.map.iter().map(|(key, value)| (key.get(), value))
+    }
+
+    /// Iterator over `(&key, &mut value)` pairs sorted by `C`.
+    pub fn iter_mut(&mut self) -> SVMapByIterMut<'_, K, V, C> {
+        self.map.iter_mut().map(|(key, value)| (key.get(), value))
+    }
+
+    /// Iterator over the keys sorted by `C`.
+    pub fn keys(&self) -> SVMapByKeys<'_, K, V, C> {
+        self.map.keys().map(Ordered::get)
+    }
+
+    /// Iterator over the values sorted by key.
+    pub fn values(&self) -> SVMapByValues<'_, K, V, C> {
+        self.map.values()
+    }
+
+    /// Iterator over mutable references to the values sorted by key.
+    pub fn values_mut(&mut self) -> SVMapByValuesMut<'_, K, V, C> {
+        self.map.values_mut()
+    }
+
+    /// Iterator over `(&key, &value)` pairs with the keys in `range`
+    /// (ordered by `C`) sorted by `C`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end` by `C` or if range `start == end` and
+    /// both bounds are `Excluded`.
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> SVMapByRange<'_, K, V, C> {
+        let bounds = (ordered_bound(range.start_bound()), ordered_bound(range.end_bound()));
+
+        self.map.range(bounds).map(|(key, value)| (key.get(), value))
+    }
+
+    /// Iterator over `(&key, &mut value)` pairs with the keys in `range`
+    /// (ordered by `C`) sorted by `C`.
+    ///
+    /// # Panics
+    ///
+    /// Panics in the same cases as [`range`](SVMapBy::range).
+    pub fn range_mut<R: RangeBounds<K>>(&mut self, range: R) -> SVMapByRangeMut<'_, K, V, C> {
+        let bounds = (ordered_bound(range.start_bound()), ordered_bound(range.end_bound()));
+
+        self.map.range_mut(bounds).map(|(key, value)| (key.get(), value))
+    }
+}
@@ -0,0 +1,312 @@
+//! In-place manipulation of [`SVMap`] entries.
+
+use crate::*;
+use super::{ SVMap, SVPair, SVMapUnit, Filter, NoFilter };
+
+
+/// A view into a single entry of an [`SVMap`], either occupied or vacant.
+///
+/// Constructed by [`SVMap::entry`].
+pub enum Entry<'a, K, V, S = strategy::Binary, F = NoFilter> {
+    /// The key is present.
+    Occupied(OccupiedEntry<'a, K, V, S, F>),
+
+    /// The key is absent.
+    Vacant(VacantEntry<'a, K, V, S, F>),
+}
+
+/// A present key: the location of its entry.
+pub struct OccupiedEntry<'a, K, V, S = strategy::Binary, F = NoFilter> {
+    map: &'a mut SVMap<K, V, S, F>,
+    unit_no: usize,
+    index: usize,
+}
+
+/// An absent key: the location of its tombstone if any.
+pub struct VacantEntry<'a, K, V, S = strategy::Binary, F = NoFilter> {
+    map: &'a mut SVMap<K, V, S, F>,
+    key: K,
+    tombstone: Option<(usize, usize)>,
+}
+
+
+impl<K: Ord, V, S: Strategy, F: Filter<K>> SVMap<K, V, S, F> {
+    /// Gets the entry for the key for in-place manipulation.
+    ///
+    /// Requires a single search: a subsequent insertion either reuses
+    /// the slot of a removed entry with the same key or goes
+    /// to the write buffer.
+    ///
+    /// ```
+    /// # #[cfg(feature="sorted_vec")] {
+    /// use dynamization::sorted_vec::SVMap;
+    ///
+    /// let mut counts = SVMap::new();
+    ///
+    /// for word in "a b a c a b".split(' ') {
+    ///     *counts.entry(word).or_insert(0) += 1;
+    /// }
+    ///
+    /// assert_eq!(counts.get("a"), Some(&3));
+    /// assert_eq!(counts.get("c"), Some(&1));
+    /// # }
+    /// ```
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, S, F> {
+        match self.locate(&key) {
+            Some((unit_no, index)) => {
+                let live = self.unit_mut(unit_no)
+                    .map_or(false, |unit| unit.vec[index].1.is_some());
+
+                if live {
+                    Entry::Occupied(OccupiedEntry { map: self, unit_no, index })
+                } else {
+                    Entry::Vacant(VacantEntry { map: self, key, tombstone: Some((unit_no, index)) })
+                }
+            }
+
+            None => Entry::Vacant(VacantEntry { map: self, key, tombstone: None }),
+        }
+    }
+
+    /// Inserts a new key-value pair into the map. Unlike 
+    /// [`insert`](SVMap::insert) replaces the key as well if it has been 
+    /// present.
+    ///
+    /// Returns the old pair. Useful for the keys whose `Ord` ignores some 
+    /// of their contents.
+    pub fn insert_replace_key(&mut self, key: K, value: V) -> Option<(K, V)> {
+        let location = self.locate(&key);
+
+        if let Some((unit_no, index)) = location {
+            let entry = &mut self.unit_mut(unit_no).unwrap().vec[index];
+
+            if entry.1.is_some() {
+                let key = core::mem::replace(&mut entry.0, key);
+
+                return entry.1.replace(value).map(|value| (key, value));
+            }
+        }
+
+        VacantEntry { map: self, key, tombstone: location }.insert(value);
+
+        None
+    }
+
+    /// Replaces the stored key equal to `key` keeping the value 
+    /// (as [`BTreeSet::replace`](alloc::collections::BTreeSet::replace) does).
+    ///
+    /// Returns the old key or `None` (and drops `key`) if the key is absent.
+    pub fn replace_key(&mut self, key: K) -> Option<K> {
+        match self.search_mut(&key) {
+            Some(entry) if entry.1.is_some() => Some(core::mem::replace(&mut entry.0, key)),
+            _ => None,
+        }
+    }
+
+    /// The entry with the smallest key.
+    pub fn first_entry(&mut self) -> Option<OccupiedEntry<'_, K, V, S, F>> {
+        let (unit_no, index) = {
+            let (key, _) = self.first_key_value()?;
+            self.locate(key)?
+        };
+
+        Some(OccupiedEntry { map: self, unit_no, index })
+    }
+
+    /// The entry with the largest key.
+    pub fn last_entry(&mut self) -> Option<OccupiedEntry<'_, K, V, S, F>> {
+        let (unit_no, index) = {
+            let (key, _) = self.last_key_value()?;
+            self.locate(key)?
+        };
+
+        Some(OccupiedEntry { map: self, unit_no, index })
+    }
+
+    /// Removes the pair with the smallest key.
+    ///
+    /// Leaves a tombstone (as [`remove`](SVMap::remove) does), so the key
+    /// is cloned out of it.
+    pub fn pop_first(&mut self) -> Option<(K, V)> where K: Clone {
+        let entry = self.first_entry()?;
+        let key = entry.key().clone();
+
+        Some((key, entry.remove()))
+    }
+
+    /// Removes the pair with the largest key.
+    ///
+    /// Leaves a tombstone (as [`remove`](SVMap::remove) does), so the key
+    /// is cloned out of it.
+    pub fn pop_last(&mut self) -> Option<(K, V)> where K: Clone {
+        let entry = self.last_entry()?;
+        let key = entry.key().clone();
+
+        Some((key, entry.remove()))
+    }
+}
+
+
+impl<'a, K: Ord, V, S: Strategy, F: Filter<K>> Entry<'a, K, V, S, F> {
+    /// The key of the entry.
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Inserts `default` if the key is absent.
+    ///
+    /// Returns an exclusive reference to the value.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    /// Inserts the result of `default()` if the key is absent.
+    ///
+    /// Returns an exclusive reference to the value.
+    pub fn or_insert_with<G: FnOnce() -> V>(self, default: G) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Inserts `V::default()` if the key is absent.
+    ///
+    /// Returns an exclusive reference to the value.
+    pub fn or_default(self) -> &'a mut V where V: Default {
+        self.or_insert_with(V::default)
+    }
+
+    /// Applies `f` to the value if the key is present.
+    pub fn and_modify<G: FnOnce(&mut V)>(self, f: G) -> Self {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+
+
+impl<'a, K: Ord, V, S: Strategy, F: Filter<K>> OccupiedEntry<'a, K, V, S, F> {
+    fn unit(&self) -> &SVMapUnit<K, V, F> {
+        // The location stays valid while the entry borrows the map.
+        self.map.dynamic.units().rev().nth(self.unit_no).unwrap()
+    }
+
+    fn into_pair(self) -> &'a mut SVPair<K, V> {
+        let index = self.index;
+
+        &mut self.map.unit_mut(self.unit_no).unwrap().vec[index]
+    }
+
+    /// The key of the entry.
+    pub fn key(&self) -> &K {
+        &self.unit().vec[self.index].0
+    }
+
+    /// Shared reference to the value.
+    pub fn get(&self) -> &V {
+        self.unit().vec[self.index].1.as_ref().unwrap()
+    }
+
+    /// Exclusive reference to the value.
+    pub fn get_mut(&mut self) -> &mut V {
+        let index = self.index;
+
+        self.map.unit_mut(self.unit_no).unwrap().vec[index].1.as_mut().unwrap()
+    }
+
+    /// Exclusive reference to the value bound to the map lifetime.
+    pub fn into_mut(self) -> &'a mut V {
+        self.into_pair().1.as_mut().unwrap()
+    }
+
+    /// Replaces the value. Returns the old one.
+    pub fn insert(&mut self, value: V) -> V {
+        core::mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes the value leaving a tombstone with the key (as
+    /// [`SVMap::remove`] does).
+    pub fn remove(self) -> V {
+        let map = self.map;
+        let index = self.index;
+        let unit = map.unit_mut(self.unit_no).unwrap();
+        let result = unit.vec[index].1.take().unwrap();
+        unit.free_count += 1;
+
+        map.len -= 1;
+        map.rebuild_if_sparse();
+
+        result
+    }
+
+    /// Removes the entry from its unit and returns the key with the value.
+    ///
+    /// Unlike [`remove`](OccupiedEntry::remove) leaves no tombstone, but takes
+    /// time linear in the unit size.
+    pub fn remove_entry(self) -> (K, V) {
+        let map = self.map;
+        let index = self.index;
+        let SVPair(key, value) = map.unit_mut(self.unit_no).unwrap().remove_at(index);
+
+        // The unit filter still covers the remaining keys.
+        map.len -= 1;
+        map.rebuild_if_sparse();
+
+        (key, value.unwrap())
+    }
+}
+
+
+impl<'a, K: Ord, V, S: Strategy, F: Filter<K>> VacantEntry<'a, K, V, S, F> {
+    /// The key which would be inserted.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Takes the key back.
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Inserts the value with the entry key.
+    ///
+    /// Returns an exclusive reference to the value.
+    pub fn insert(self, value: V) -> &'a mut V {
+        let VacantEntry { map, key, tombstone } = self;
+        map.len += 1;
+
+        if let Some((unit_no, index)) = tombstone {
+            // Resurrect the removed entry in place.
+            let unit = map.unit_mut(unit_no).unwrap();
+            unit.free_count -= 1;
+
+            let entry = &mut unit.vec[index];
+            entry.0 = key;
+
+            return entry.1.insert(value);
+        }
+
+        // `Buffered::insert` flushes a full buffer first and puts the item
+        // into the new one.
+        let index = match map.dynamic.buffer() {
+            Some(buffer) if buffer.len() < map.dynamic.capacity() => {
+                buffer.vec.partition_point(|entry| entry.0 < key)
+            }
+
+            _ => 0,
+        };
+
+        map.dynamic.insert(SVPair(key, Some(value)));
+
+        map.dynamic.buffer_mut().unwrap().vec[index].1.as_mut().unwrap()
+    }
+}
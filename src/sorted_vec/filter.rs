@@ -0,0 +1,139 @@
+//! Per-unit key filters.
+
+use crate::*;
+use alloc::vec;
+use core::hash::{ Hash, Hasher };
+
+
+/// A probabilistic set of keys built for each unit of an [`SVMap`](super::SVMap).
+///
+/// Lookups skip the units whose filters report that the key is
+/// definitely absent.
+///
+/// Filters are built on merges and on bulk construction: the default value
+/// is used for the units without a filter and must not exclude any key.
+///
+/// A filter must treat a key and all its borrowed forms in the same way
+/// (e.g. [`Hash`] does so for the types implementing
+/// [`Borrow`](core::borrow::Borrow) correctly).
+pub trait Filter<K: ?Sized>: Default {
+    /// Builds a filter for a set of keys.
+    fn build<'a, I>(keys: I) -> Self where
+        I: ExactSizeIterator<Item = &'a K>,
+        K: 'a;
+
+    /// Returns `false` if the key is definitely absent.
+    fn may_contain(&self, key: &K) -> bool;
+}
+
+
+/// A filter which doesn't exclude anything.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoFilter;
+
+impl<K: ?Sized> Filter<K> for NoFilter {
+    fn build<'a, I>(_keys: I) -> Self where
+        I: ExactSizeIterator<Item = &'a K>,
+        K: 'a
+    {
+        NoFilter
+    }
+
+    fn may_contain(&self, _key: &K) -> bool {
+        true
+    }
+}
+
+
+/// A Bloom filter using `BITS_PER_KEY` bits for each key.
+///
+/// The false positive rate is approximately `0.6185^BITS_PER_KEY`:
+/// about 1% for 10 bits per key ([`Bloom10`]).
+#[derive(Clone, Debug, Default)]
+pub struct Bloom<const BITS_PER_KEY: usize> {
+    bits: Vec<u64>,
+    hash_count: u32,
+}
+
+/// A Bloom filter with about 1% false positives.
+pub type Bloom10 = Bloom<10>;
+
+impl<K: Hash + ?Sized, const BITS_PER_KEY: usize> Filter<K> for Bloom<BITS_PER_KEY> {
+    fn build<'a, I>(keys: I) -> Self where
+        I: ExactSizeIterator<Item = &'a K>,
+        K: 'a
+    {
+        let bit_count = keys.len() * BITS_PER_KEY;
+
+        if bit_count == 0 {
+            return Self::default();
+        }
+
+        // The optimal hash count is `BITS_PER_KEY * ln 2`.
+        let hash_count = (BITS_PER_KEY * 69 / 100).clamp(1, 30) as u32;
+        let mut bits = vec![0u64; (bit_count + 63) / 64];
+        let modulus = bits.len() as u64 * 64;
+
+        for key in keys {
+            for bit in probes(key, hash_count, modulus) {
+                bits[(bit / 64) as usize] |= 1 << (bit % 64);
+            }
+        }
+
+        Bloom { bits, hash_count }
+    }
+
+    fn may_contain(&self, key: &K) -> bool {
+        if self.bits.is_empty() {
+            return true;
+        }
+
+        let modulus = self.bits.len() as u64 * 64;
+
+        probes(key, self.hash_count, modulus).all(|bit| {
+            self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0
+        })
+    }
+}
+
+/// Bit positions for a key (double hashing).
+fn probes<K: Hash + ?Sized>(key: &K, hash_count: u32, modulus: u64)
+    -> impl Iterator<Item = u64>
+{
+    let mut hasher = FnvHasher::default();
+    key.hash(&mut hasher);
+
+    let hash = hasher.finish();
+    let delta = hash.rotate_left(32) | 1;
+
+    (0..hash_count as u64).map(move |i| {
+        hash.wrapping_add(i.wrapping_mul(delta)) % modulus
+    })
+}
+
+
+/// FNV-1a with a final avalanche step: `core` has no usable hasher.
+struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        FnvHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        // The splitmix64 finalizer
+        let mut x = self.0;
+        x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        x ^ (x >> 31)
+    }
+}
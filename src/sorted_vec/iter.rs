@@ -0,0 +1,580 @@
+//! Sorted iteration over [`SVMap`] entries.
+//!
+//! All the iterators are lazy k-way merges over the units: each step
+//! compares the current heads of all the units (there are only
+//! `O(log N)` of them) and skips removed entries.
+
+use crate::*;
+use super::{ SVMap, SVPair, Filter };
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::ops::{ Bound, RangeBounds };
+
+
+/// An entry of a unit as seen by a [`Merge`].
+pub(super) trait MergeItem {
+    type Key: Ord;
+    type Output;
+
+    fn key(&self) -> &Self::Key;
+
+    /// Returns `None` for a removed entry.
+    fn into_output(self) -> Option<Self::Output>;
+}
+
+impl<'a, K: Ord, V> MergeItem for &'a SVPair<K, V> {
+    type Key = K;
+    type Output = (&'a K, &'a V);
+
+    fn key(&self) -> &K {
+        &self.0
+    }
+
+    fn into_output(self) -> Option<Self::Output> {
+        self.1.as_ref().map(|v| (&self.0, v))
+    }
+}
+
+impl<'a, K: Ord, V> MergeItem for &'a mut SVPair<K, V> {
+    type Key = K;
+    type Output = (&'a K, &'a mut V);
+
+    fn key(&self) -> &K {
+        &self.0
+    }
+
+    fn into_output(self) -> Option<Self::Output> {
+        let SVPair(k, v) = self;
+        let k: &'a K = k;
+        v.as_mut().map(move |v| (k, v))
+    }
+}
+
+impl<K: Ord, V> MergeItem for SVPair<K, V> {
+    type Key = K;
+    type Output = (K, V);
+
+    fn key(&self) -> &K {
+        &self.0
+    }
+
+    fn into_output(self) -> Option<Self::Output> {
+        let SVPair(k, v) = self;
+        v.map(|v| (k, v))
+    }
+}
+
+
+/// A double-ended iterator with peeked ends.
+struct Cursor<I: Iterator> {
+    iter: I,
+    front: Option<I::Item>,
+    back: Option<I::Item>,
+}
+
+impl<I: DoubleEndedIterator> Cursor<I> {
+    fn peek_front(&mut self) -> Option<&I::Item> {
+        if self.front.is_none() {
+            self.front = self.iter.next().or_else(|| self.back.take());
+        }
+
+        self.front.as_ref()
+    }
+
+    fn peek_back(&mut self) -> Option<&I::Item> {
+        if self.back.is_none() {
+            self.back = self.iter.next_back().or_else(|| self.front.take());
+        }
+
+        self.back.as_ref()
+    }
+}
+
+
+/// A lazy k-way merge of sorted sequences skipping removed entries.
+pub(super) struct Merge<I: Iterator> {
+    cursors: Vec<Cursor<I>>,
+}
+
+impl<I> Merge<I> where
+    I: DoubleEndedIterator,
+    I::Item: MergeItem,
+{
+    pub(super) fn new<U: IntoIterator<Item = I>>(iters: U) -> Self {
+        Merge {
+            cursors: iters.into_iter().map(|iter| {
+                Cursor { iter, front: None, back: None }
+            }).collect(),
+        }
+    }
+
+    pub(super) fn next_front(&mut self) -> Option<<I::Item as MergeItem>::Output> {
+        loop {
+            for cursor in &mut self.cursors {
+                cursor.peek_front();
+            }
+
+            let best = self.cursors.iter_mut()
+                .filter(|cursor| cursor.front.is_some())
+                .min_by(|a, b| {
+                    let a = a.front.as_ref().map(|x| x.key());
+                    let b = b.front.as_ref().map(|x| x.key());
+                    a.cmp(&b)
+                })?;
+
+            let item = best.front.take()?;
+
+            if let Some(output) = item.into_output() {
+                return Some(output);
+            }
+        }
+    }
+
+    pub(super) fn next_back(&mut self) -> Option<<I::Item as MergeItem>::Output> {
+        loop {
+            for cursor in &mut self.cursors {
+                cursor.peek_back();
+            }
+
+            let best = self.cursors.iter_mut()
+                .filter(|cursor| cursor.back.is_some())
+                .max_by(|a, b| {
+                    let a = a.back.as_ref().map(|x| x.key());
+                    let b = b.back.as_ref().map(|x| x.key());
+                    a.cmp(&b)
+                })?;
+
+            let item = best.back.take()?;
+
+            if let Some(output) = item.into_output() {
+                return Some(output);
+            }
+        }
+    }
+}
+
+
+macro_rules! exact_size_iterator {
+    ($name:ident, $item:ty, |$x:ident| $map:expr) => {
+        impl<'a, K: Ord, V> Iterator for $name<'a, K, V> {
+            type Item = $item;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                let $x = self.merge.next_front()?;
+                self.len -= 1;
+                Some($map)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.len, Some(self.len))
+            }
+        }
+
+        impl<'a, K: Ord, V> DoubleEndedIterator for $name<'a, K, V> {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                let $x = self.merge.next_back()?;
+                self.len -= 1;
+                Some($map)
+            }
+        }
+
+        impl<'a, K: Ord, V> ExactSizeIterator for $name<'a, K, V> {}
+
+        impl<'a, K: Ord, V> core::iter::FusedIterator for $name<'a, K, V> {}
+    }
+}
+
+
+/// Iterator over `(&key, &value)` pairs of an [`SVMap`] sorted by key.
+pub struct SVMapIter<'a, K, V> {
+    merge: Merge<core::slice::Iter<'a, SVPair<K, V>>>,
+    len: usize,
+}
+
+exact_size_iterator!(SVMapIter, (&'a K, &'a V), |kv| kv);
+
+
+/// Iterator over `(&key, &mut value)` pairs of an [`SVMap`] sorted by key.
+pub struct SVMapIterMut<'a, K, V> {
+    merge: Merge<core::slice::IterMut<'a, SVPair<K, V>>>,
+    len: usize,
+}
+
+exact_size_iterator!(SVMapIterMut, (&'a K, &'a mut V), |kv| kv);
+
+
+/// Iterator over the keys of an [`SVMap`] in sorted order.
+pub struct SVMapKeys<'a, K, V> {
+    merge: Merge<core::slice::Iter<'a, SVPair<K, V>>>,
+    len: usize,
+}
+
+exact_size_iterator!(SVMapKeys, &'a K, |kv| kv.0);
+
+
+/// Iterator over the values of an [`SVMap`] sorted by key.
+pub struct SVMapValues<'a, K, V> {
+    merge: Merge<core::slice::Iter<'a, SVPair<K, V>>>,
+    len: usize,
+}
+
+exact_size_iterator!(SVMapValues, &'a V, |kv| kv.1);
+
+
+/// Iterator over mutable references to the values of an [`SVMap`]
+/// sorted by key.
+pub struct SVMapValuesMut<'a, K, V> {
+    merge: Merge<core::slice::IterMut<'a, SVPair<K, V>>>,
+    len: usize,
+}
+
+exact_size_iterator!(SVMapValuesMut, &'a mut V, |kv| kv.1);
+
+
+/// Owning iterator over key-value pairs of an [`SVMap`] sorted by key.
+pub struct SVMapKV<K, V> {
+    merge: Merge<alloc::vec::IntoIter<SVPair<K, V>>>,
+    len: usize,
+}
+
+impl<K: Ord, V> Iterator for SVMapKV<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let kv = self.merge.next_front()?;
+        self.len -= 1;
+        Some(kv)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<K: Ord, V> DoubleEndedIterator for SVMapKV<K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let kv = self.merge.next_back()?;
+        self.len -= 1;
+        Some(kv)
+    }
+}
+
+impl<K: Ord, V> ExactSizeIterator for SVMapKV<K, V> {}
+
+impl<K: Ord, V> core::iter::FusedIterator for SVMapKV<K, V> {}
+
+
+/// Iterator over a key range of an [`SVMap`]. Yields `(&key, &value)` pairs.
+pub struct SVMapRange<'a, K, V> {
+    merge: Merge<core::slice::Iter<'a, SVPair<K, V>>>,
+}
+
+/// Iterator over a key range of an [`SVMap`]. Yields `(&key, &mut value)` pairs.
+pub struct SVMapRangeMut<'a, K, V> {
+    merge: Merge<core::slice::IterMut<'a, SVPair<K, V>>>,
+}
+
+macro_rules! range_iterator {
+    ($name:ident, $item:ty) => {
+        impl<'a, K: Ord, V> Iterator for $name<'a, K, V> {
+            type Item = $item;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.merge.next_front()
+            }
+        }
+
+        impl<'a, K: Ord, V> DoubleEndedIterator for $name<'a, K, V> {
+            fn next_back(&mut self) -> Option<Self::Item> {
+                self.merge.next_back()
+            }
+        }
+
+        impl<'a, K: Ord, V> core::iter::FusedIterator for $name<'a, K, V> {}
+    }
+}
+
+range_iterator!(SVMapRange, (&'a K, &'a V));
+range_iterator!(SVMapRangeMut, (&'a K, &'a mut V));
+
+
+/// A lazy merge join of two [`SVMap`]s. Yields `(&key, Option<&V1>, Option<&V2>)`.
+pub struct SVMapJoin<'a, K: Ord, V1, V2> {
+    left: core::iter::Peekable<SVMapIter<'a, K, V1>>,
+    right: core::iter::Peekable<SVMapIter<'a, K, V2>>,
+}
+
+impl<'a, K: Ord, V1, V2> Iterator for SVMapJoin<'a, K, V1, V2> {
+    type Item = (&'a K, Option<&'a V1>, Option<&'a V2>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let order = match (self.left.peek(), self.right.peek()) {
+            (None, None) => { return None; }
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some((x, _)), Some((y, _))) => x.cmp(y),
+        };
+
+        match order {
+            Ordering::Less => self.left.next().map(|(k, v)| (k, Some(v), None)),
+            Ordering::Greater => self.right.next().map(|(k, v)| (k, None, Some(v))),
+
+            Ordering::Equal => {
+                let (k, v1) = self.left.next()?;
+                let (_, v2) = self.right.next()?;
+                Some((k, Some(v1), Some(v2)))
+            }
+        }
+    }
+}
+
+impl<'a, K: Ord, V1, V2> core::iter::FusedIterator for SVMapJoin<'a, K, V1, V2> {}
+
+
+/// Panics on the ranges rejected by [`BTreeMap::range`](alloc::collections::BTreeMap::range).
+pub(super) fn check_range<Q: Ord + ?Sized, R: RangeBounds<Q>>(range: &R, container: &str) {
+    use Bound::*;
+
+    match (range.start_bound(), range.end_bound()) {
+        (Excluded(s), Excluded(e)) if s == e => {
+            panic!("range start and end are equal and excluded in {}", container)
+        }
+
+        (Included(s) | Excluded(s), Included(e) | Excluded(e)) if s > e => {
+            panic!("range start is greater than range end in {}", container)
+        }
+
+        _ => {}
+    }
+}
+
+/// Bounds of the part of a sorted unit lying inside the range.
+pub(super) fn unit_range<T, K, Q, R, G>(vec: &[T], range: &R, key: G) -> core::ops::Range<usize> where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+    R: RangeBounds<Q>,
+    G: Fn(&T) -> &K,
+{
+    let start = match range.start_bound() {
+        Bound::Included(s) => vec.partition_point(|x| key(x).borrow() < s),
+        Bound::Excluded(s) => vec.partition_point(|x| key(x).borrow() <= s),
+        Bound::Unbounded => 0,
+    };
+
+    let end = match range.end_bound() {
+        Bound::Included(e) => vec.partition_point(|x| key(x).borrow() <= e),
+        Bound::Excluded(e) => vec.partition_point(|x| key(x).borrow() < e),
+        Bound::Unbounded => vec.len(),
+    };
+
+    start..core::cmp::max(start, end)
+}
+
+
+impl<K: Ord, V, S: Strategy, F: Filter<K>> SVMap<K, V, S, F> {
+    /// Iterator over `(&key, &value)` pairs with the keys in `range`
+    /// sorted by key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if range `start > end` or if range `start == end` and 
+    /// both bounds are `Excluded` (as [`BTreeMap::range`](alloc::collections::BTreeMap::range) does).
+    pub fn range<Q, R>(&self, range: R) -> SVMapRange<'_, K, V> where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        check_range(&range, "SVMap");
+
+        self.range_by(&range, |slice| unit_range(slice, &range, |entry| &entry.0))
+    }
+
+    /// Iterator over the parts of the units given by `bounds`, which must 
+    /// form a key range inside `fences`.
+    ///
+    /// The units with the keys outside `fences` are skipped without 
+    /// a search.
+    pub(super) fn range_by<Q, R, B>(&self, fences: &R, bounds: B) -> SVMapRange<'_, K, V> where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+        B: Fn(&[SVPair<K, V>]) -> core::ops::Range<usize>
+    {
+        let slices = self.dynamic.units()
+            .filter(|unit| unit.fences_overlap(fences))
+            .flat_map(|unit| unit.live_slices());
+
+        SVMapRange {
+            merge: Merge::new(slices.filter_map(|slice| {
+                let bounds = bounds(slice);
+
+                if bounds.is_empty() { return None; }
+
+                Some(slice[bounds].iter())
+            })),
+        }
+    }
+
+    /// Iterator over `(&key, &mut value)` pairs with the keys in `range`
+    /// sorted by key.
+    ///
+    /// # Panics
+    ///
+    /// Panics in the same cases as [`range`](SVMap::range).
+    pub fn range_mut<Q, R>(&mut self, range: R) -> SVMapRangeMut<'_, K, V> where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        check_range(&range, "SVMap");
+
+        let slices = self.dynamic.units_mut()
+            .filter(|unit| unit.fences_overlap(&range))
+            .flat_map(|unit| unit.live_slices_mut());
+
+        SVMapRangeMut {
+            merge: Merge::new(slices.filter_map(|slice| {
+                let bounds = unit_range(slice, &range, |entry| &entry.0);
+
+                if bounds.is_empty() { return None; }
+
+                Some(slice[bounds].iter_mut())
+            })),
+        }
+    }
+
+    /// Iterator over `(&key, &value)` pairs sorted by key.
+    pub fn iter(&self) -> SVMapIter<'_, K, V> {
+        SVMapIter {
+            merge: Merge::new(self.dynamic.units()
+                .flat_map(|unit| unit.live_slices())
+                .map(|slice| slice.iter())),
+            len: self.len,
+        }
+    }
+
+    /// Iterator over `(&key, &mut value)` pairs sorted by key.
+    pub fn iter_mut(&mut self) -> SVMapIterMut<'_, K, V> {
+        SVMapIterMut {
+            merge: Merge::new(self.dynamic.units_mut()
+                .flat_map(|unit| unit.live_slices_mut())
+                .map(|slice| slice.iter_mut())),
+            len: self.len,
+        }
+    }
+
+    /// Iterator over the keys in sorted order.
+    pub fn keys(&self) -> SVMapKeys<'_, K, V> {
+        SVMapKeys {
+            merge: Merge::new(self.dynamic.units()
+                .flat_map(|unit| unit.live_slices())
+                .map(|slice| slice.iter())),
+            len: self.len,
+        }
+    }
+
+    /// Iterator over the values sorted by key.
+    pub fn values(&self) -> SVMapValues<'_, K, V> {
+        SVMapValues {
+            merge: Merge::new(self.dynamic.units()
+                .flat_map(|unit| unit.live_slices())
+                .map(|slice| slice.iter())),
+            len: self.len,
+        }
+    }
+
+    /// Iterator over mutable references to the values sorted by key.
+    pub fn values_mut(&mut self) -> SVMapValuesMut<'_, K, V> {
+        SVMapValuesMut {
+            merge: Merge::new(self.dynamic.units_mut()
+                .flat_map(|unit| unit.live_slices_mut())
+                .map(|slice| slice.iter_mut())),
+            len: self.len,
+        }
+    }
+
+    /// A lazy merge join with another map: yields every key present in 
+    /// either map with its values from `self` and from `other` in sorted order.
+    ///
+    /// ```
+    /// # #[cfg(feature="sorted_vec")] {
+    /// use dynamization::sorted_vec::SVMap;
+    ///
+    /// let a: SVMap<_, _> = vec![(1, 'a'), (2, 'b')].into_iter().collect();
+    /// let b: SVMap<_, _> = vec![(2, 20), (3, 30)].into_iter().collect();
+    ///
+    /// assert!(a.merge_join(&b).eq(vec![
+    ///     (&1, Some(&'a'), None),
+    ///     (&2, Some(&'b'), Some(&20)),
+    ///     (&3, None, Some(&30)),
+    /// ]));
+    /// # }
+    /// ```
+    pub fn merge_join<'a, V2, S2, F2>(&'a self, other: &'a SVMap<K, V2, S2, F2>) -> SVMapJoin<'a, K, V, V2> where
+        S2: Strategy,
+        F2: Filter<K>,
+    {
+        SVMapJoin {
+            left: self.iter().peekable(),
+            right: other.iter().peekable(),
+        }
+    }
+
+    /// The pair with the smallest key.
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.iter().next()
+    }
+
+    /// The pair with the largest key.
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.iter().next_back()
+    }
+
+    /// The pair with the largest key less than or equal to `key`.
+    pub fn floor<Q: Ord + ?Sized>(&self, key: &Q) -> Option<(&K, &V)> where
+        K: Borrow<Q>
+    {
+        self.range((Bound::Unbounded, Bound::Included(key))).next_back()
+    }
+
+    /// The pair with the smallest key greater than or equal to `key`.
+    pub fn ceiling<Q: Ord + ?Sized>(&self, key: &Q) -> Option<(&K, &V)> where
+        K: Borrow<Q>
+    {
+        self.range((Bound::Included(key), Bound::Unbounded)).next()
+    }
+}
+
+
+impl<K: Ord, V, S, F> IntoIterator for SVMap<K, V, S, F> {
+    type Item = (K, V);
+    type IntoIter = SVMapKV<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        SVMapKV {
+            merge: Merge::new(self.dynamic.into_iter().map(|mut unit| {
+                unit.purge();
+                unit.vec.into_iter()
+            })),
+            len: self.len,
+        }
+    }
+}
+
+impl<'a, K: Ord, V, S: Strategy, F: Filter<K>> IntoIterator for &'a SVMap<K, V, S, F> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = SVMapIter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K: Ord, V, S: Strategy, F: Filter<K>> IntoIterator for &'a mut SVMap<K, V, S, F> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = SVMapIterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
@@ -0,0 +1,335 @@
+//! Log-structured map with blind writes.
+
+use crate::*;
+use crate::buffered::Buffered;
+use alloc::vec;
+use core::cmp::Ordering;
+
+
+/// An associative array with blind writes (a log-structured merge tree).
+///
+/// Unlike [`SVMap`](super::SVMap) doesn't search the units on insertion
+/// and removal: both just add a new version of the entry (a removal adds
+/// a tombstone) tagged with a sequence number. Lookups return the newest
+/// version.
+///
+/// Merges keep only the newest version of each key. A merge also drops
+/// the tombstones if the resulting unit contains all the writes from
+/// the first one up to its newest one: then no other unit can have an older
+/// version to shadow. [`compact`](SVLsmMap::compact) merges everything into
+/// a single unit and drops all the tombstones.
+///
+/// Lookups are slower than in `SVMap`: a unit is skipped only if all its
+/// writes are older than the version already found. Removals are also
+/// slower as each of them adds an entry to be merged later.
+///
+/// The number of items is unknown without a search, so there is no `len`:
+/// see [`stored_len`](SVLsmMap::stored_len).
+///
+/// ```
+/// # #[cfg(feature="sorted_vec")] {
+/// use dynamization::sorted_vec::SVLsmMap;
+///
+/// let mut map = SVLsmMap::new();
+///
+/// map.insert("a", 1);
+/// map.insert("b", 2);
+/// map.insert("a", 3);
+/// map.remove("b");
+///
+/// assert_eq!(map.get("a"), Some(&3));
+/// assert_eq!(map.get("b"), None);
+///
+/// map.compact();
+/// assert_eq!(map.stored_len(), 1);
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct SVLsmMap<K, V, S = strategy::Binary> {
+    dynamic: Buffered<LsmUnit<K, V>, S>,
+    seq: u64,
+}
+
+/// A version of an entry: `value` is `None` for a tombstone.
+#[derive(Clone, Debug)]
+struct LsmEntry<K, V> {
+    key: K,
+    seq: u64,
+    value: Option<V>,
+}
+
+/// A sorted vector of entries with unique keys.
+#[derive(Clone, Debug)]
+struct LsmUnit<K, V> {
+    vec: Vec<LsmEntry<K, V>>,
+    min_seq: u64,
+    max_seq: u64,
+
+    /// The number of writes absorbed including the shadowed ones.
+    writes: u64,
+}
+
+impl<K, V> LsmUnit<K, V> {
+    /// Returns `true` if the unit contains all the writes from the first one
+    /// up to `max_seq` (each write has a distinct sequence number).
+    fn is_complete(&self) -> bool {
+        self.min_seq == 0 && self.writes == self.max_seq + 1
+    }
+
+    fn find<Q: Ord + ?Sized>(&self, key: &Q) -> Option<usize> where
+        K: core::borrow::Borrow<Q>
+    {
+        match (self.vec.first(), self.vec.last()) {
+            (Some(first), Some(last)) => {
+                if key < first.key.borrow() || last.key.borrow() < key {
+                    return None;
+                }
+            }
+
+            _ => { return None; }
+        }
+
+        self.vec.binary_search_by(|entry| entry.key.borrow().cmp(key)).ok()
+    }
+}
+
+impl<K: Ord, V> Static for LsmUnit<K, V> {
+    fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    fn merge_with(self, other: Self) -> Self {
+        let mut result = LsmUnit {
+            vec: Vec::with_capacity(self.vec.len() + other.vec.len()),
+            min_seq: self.min_seq.min(other.min_seq),
+            max_seq: self.max_seq.max(other.max_seq),
+            writes: self.writes + other.writes,
+        };
+
+        let complete = result.is_complete();
+        let mut a = self.vec.into_iter().peekable();
+        let mut b = other.vec.into_iter().peekable();
+
+        loop {
+            let entry = match (a.peek(), b.peek()) {
+                (None, None) => { break; }
+                (Some(_), None) => a.next(),
+                (None, Some(_)) => b.next(),
+
+                (Some(x), Some(y)) => match x.key.cmp(&y.key) {
+                    Ordering::Less => a.next(),
+                    Ordering::Greater => b.next(),
+
+                    // Drop the older version.
+                    Ordering::Equal => if x.seq > y.seq {
+                        b.next();
+                        a.next()
+                    } else {
+                        a.next();
+                        b.next()
+                    }
+                }
+            };
+
+            if let Some(entry) = entry {
+                if !(complete && entry.value.is_none()) {
+                    result.vec.push(entry);
+                }
+            }
+        }
+
+        result
+    }
+}
+
+impl<K: Ord, V> Singleton for LsmUnit<K, V> {
+    type Item = LsmEntry<K, V>;
+
+    fn singleton(item: Self::Item) -> Self {
+        LsmUnit {
+            min_seq: item.seq,
+            max_seq: item.seq,
+            writes: 1,
+            vec: vec![item],
+        }
+    }
+}
+
+impl<K: Ord, V> Insert for LsmUnit<K, V> {
+    /// Overwrites an older version of the key if present.
+    fn insert(&mut self, item: Self::Item) {
+        self.min_seq = self.min_seq.min(item.seq);
+        self.max_seq = self.max_seq.max(item.seq);
+        self.writes += 1;
+
+        match self.vec.binary_search_by(|entry| entry.key.cmp(&item.key)) {
+            Ok(index) => { self.vec[index] = item; }
+            Err(index) => { self.vec.insert(index, item); }
+        }
+    }
+}
+
+
+impl<K: Ord, V> SVLsmMap<K, V> {
+    /// Uses the [`Binary`](strategy::Binary) strategy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses a specified strategy.
+    pub fn with_strategy<S: Strategy>() -> SVLsmMap<K, V, S> {
+        SVLsmMap::default()
+    }
+}
+
+impl<K: Ord, V, S: Strategy> Default for SVLsmMap<K, V, S> {
+    fn default() -> Self {
+        SVLsmMap {
+            dynamic: Buffered::new(),
+            seq: 0,
+        }
+    }
+}
+
+
+impl<K: Ord, V, S: Strategy> SVLsmMap<K, V, S> {
+    /// The number of stored versions including the shadowed ones and
+    /// the tombstones.
+    pub fn stored_len(&self) -> usize {
+        self.dynamic.len()
+    }
+
+    fn write(&mut self, key: K, value: Option<V>) {
+        let seq = self.seq;
+        self.seq += 1;
+
+        self.dynamic.insert(LsmEntry { key, seq, value });
+    }
+
+    /// Inserts a new version of the entry without searching for the old one.
+    pub fn insert(&mut self, key: K, value: V) {
+        self.write(key, Some(value));
+    }
+
+    /// Adds a tombstone for the key without searching for the entry.
+    pub fn remove(&mut self, key: K) {
+        self.write(key, None);
+    }
+
+    /// Finds the newest version of the key.
+    ///
+    /// Returns the number of its unit in `units()` and its index in the unit.
+    // The buffer and the smaller units are usually newer and probed first:
+    // then the units with only older writes are skipped.
+    fn locate<Q: Ord + ?Sized>(&self, key: &Q) -> Option<(usize, usize)> where
+        K: core::borrow::Borrow<Q>
+    {
+        let mut newest: Option<(usize, usize, u64)> = None;
+
+        for (unit_no, unit) in self.dynamic.units().enumerate() {
+            if let Some((_, _, seq)) = newest {
+                if unit.max_seq < seq { continue; }
+            }
+
+            if let Some(index) = unit.find(key) {
+                let seq = unit.vec[index].seq;
+
+                if newest.map_or(true, |(_, _, newest_seq)| newest_seq < seq) {
+                    newest = Some((unit_no, index, seq));
+                }
+            }
+        }
+
+        newest.map(|(unit_no, index, _)| (unit_no, index))
+    }
+
+    /// Searches for the newest version of the key.
+    ///
+    /// Returns a shared reference to the value or `None` if the key
+    /// is absent or removed.
+    pub fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&V> where
+        K: core::borrow::Borrow<Q>
+    {
+        let (unit_no, index) = self.locate(key)?;
+
+        self.dynamic.units().nth(unit_no)?.vec[index].value.as_ref()
+    }
+
+    /// Searches for the newest version of the key.
+    ///
+    /// Returns an exclusive reference to the value or `None` if the key
+    /// is absent or removed.
+    pub fn get_mut<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<&mut V> where
+        K: core::borrow::Borrow<Q>
+    {
+        let (unit_no, index) = self.locate(key)?;
+
+        self.dynamic.units_mut().nth(unit_no)?.vec[index].value.as_mut()
+    }
+
+    /// Returns `true` if the newest version of the key is not removed.
+    pub fn contains_key<Q: Ord + ?Sized>(&self, key: &Q) -> bool where
+        K: core::borrow::Borrow<Q>
+    {
+        self.get(key).is_some()
+    }
+
+    /// Merges all the units into a single one dropping the shadowed
+    /// versions and the tombstones.
+    pub fn compact(&mut self) {
+        let dynamic = core::mem::take(&mut self.dynamic);
+
+        if let Some(mut unit) = dynamic.try_collect() {
+            // A single unit might have not been merged at all.
+            unit.vec.retain(|entry| entry.value.is_some());
+
+            if !unit.vec.is_empty() {
+                self.dynamic.add_unit(unit);
+            }
+        }
+    }
+
+    /// Removes all elements from the map.
+    pub fn clear(&mut self) {
+        self.dynamic.clear();
+        self.seq = 0;
+    }
+}
+
+impl<K: Ord, V, S: Strategy> core::iter::FromIterator<(K, V)> for SVLsmMap<K, V, S> {
+    /// The later pairs overwrite the earlier ones with the same key.
+    fn from_iter<I: IntoIterator<Item=(K, V)>>(iter: I) -> Self {
+        let mut pairs: Vec<(K, V)> = iter.into_iter().collect();
+        let seq = pairs.len() as u64;
+
+        // Stable: the last one of equal keys stays the last.
+        pairs.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut vec: Vec<LsmEntry<K, V>> = Vec::with_capacity(pairs.len());
+
+        for (seq, (key, value)) in pairs.into_iter().enumerate() {
+            let entry = LsmEntry { key, seq: seq as u64, value: Some(value) };
+
+            match vec.last_mut() {
+                Some(last) if last.key == entry.key => { *last = entry; }
+                _ => { vec.push(entry); }
+            }
+        }
+
+        let mut map = SVLsmMap {
+            dynamic: Buffered::new(),
+            seq,
+        };
+
+        if !vec.is_empty() {
+            map.dynamic.add_unit(LsmUnit {
+                vec,
+                min_seq: 0,
+                max_seq: seq - 1,
+                writes: seq,
+            });
+        }
+
+        map
+    }
+}
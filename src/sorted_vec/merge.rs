@@ -0,0 +1,332 @@
+//! Blind updates combined by a merge operator.
+
+use crate::*;
+use crate::buffered::Buffered;
+use super::{ SVPair, merge_vecs_with };
+use alloc::vec;
+use core::marker::PhantomData;
+
+
+/// An associative operation on values: e.g. addition for counters or
+/// concatenation for lists.
+///
+/// Must satisfy `merge(merge(a, b), c) == merge(a, merge(b, c))`.
+/// An identity element is not needed.
+pub trait MergeOperator<V> {
+    /// Combines an older operand with a newer one.
+    fn merge(older: V, newer: V) -> V;
+}
+
+
+/// Adds the operands up.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sum;
+
+impl<V: core::ops::Add<Output = V>> MergeOperator<V> for Sum {
+    fn merge(older: V, newer: V) -> V {
+        older + newer
+    }
+}
+
+
+/// Appends the newer operand to the older one.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Concat;
+
+impl<T> MergeOperator<Vec<T>> for Concat {
+    fn merge(mut older: Vec<T>, newer: Vec<T>) -> Vec<T> {
+        older.extend(newer);
+        older
+    }
+}
+
+
+/// An associative array with blind updates.
+///
+/// Besides replacing the value ([`insert`](SVMergeMap::insert)) and removing
+/// it ([`remove`](SVMergeMap::remove)) a value can be updated with an operand
+/// ([`merge`](SVMergeMap::merge)): none of these operations search the units.
+///
+/// Merges of the units combine the entries with equal keys via
+/// the [`MergeOperator`]. Lookups fold the entries for the key from
+/// the newest unit to the oldest one, stopping at a replacement or a removal.
+///
+/// Uses the [`SimpleBinary`](strategy::SimpleBinary) strategy: its units
+/// are ordered by age and only adjacent units are merged, so the operator
+/// need not be commutative.
+///
+/// ```
+/// # #[cfg(feature="sorted_vec")] {
+/// use dynamization::sorted_vec::{ SVMergeMap, Sum };
+///
+/// let mut counts = SVMergeMap::<&str, u32, Sum>::new();
+///
+/// for word in "a b a c a b".split(' ') {
+///     counts.merge(word, 1);
+/// }
+///
+/// counts.remove("c");
+///
+/// assert_eq!(counts.get("a"), Some(3));
+/// assert_eq!(counts.get("c"), None);
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct SVMergeMap<K, V, M> {
+    dynamic: Buffered<MergeUnit<K, V, M>, strategy::SimpleBinary>,
+    seq: u64,
+}
+
+/// A pending write: `None` in [`SVPair`] stands for a removal.
+#[derive(Clone, Debug)]
+enum Operand<V> {
+    Put(V),
+    Merge(V),
+}
+
+/// A sorted vector of entries with unique keys.
+#[derive(Clone, Debug)]
+struct MergeUnit<K, V, M> {
+    vec: Vec<SVPair<K, Operand<V>>>,
+    min_seq: u64,
+    max_seq: u64,
+
+    /// The number of writes absorbed including the combined ones.
+    writes: u64,
+
+    operator: PhantomData<M>,
+}
+
+/// Applies a newer write on top of an older one.
+fn combine<K, V, M: MergeOperator<V>>(
+    older: SVPair<K, Operand<V>>, newer: SVPair<K, Operand<V>>
+) -> SVPair<K, Operand<V>> {
+    let SVPair(key, newer) = newer;
+
+    SVPair(key, combine_values::<V, M>(older.1, newer))
+}
+
+/// Applies a newer write on top of an older one for the same key.
+fn combine_values<V, M: MergeOperator<V>>(
+    older: Option<Operand<V>>, newer: Option<Operand<V>>
+) -> Option<Operand<V>> {
+    match (older, newer) {
+        (_, None) => None,
+        (_, Some(Operand::Put(value))) => Some(Operand::Put(value)),
+        (None, Some(Operand::Merge(delta))) => Some(Operand::Put(delta)),
+
+        (Some(Operand::Put(value)), Some(Operand::Merge(delta))) => {
+            Some(Operand::Put(M::merge(value, delta)))
+        }
+
+        (Some(Operand::Merge(first)), Some(Operand::Merge(delta))) => {
+            Some(Operand::Merge(M::merge(first, delta)))
+        }
+    }
+}
+
+impl<K, V, M> MergeUnit<K, V, M> {
+    /// Returns `true` if the unit contains all the writes from the first one
+    /// up to `max_seq`.
+    fn is_complete(&self) -> bool {
+        self.min_seq == 0 && self.writes == self.max_seq + 1
+    }
+
+    fn find<Q: Ord + ?Sized>(&self, key: &Q) -> Option<&Option<Operand<V>>> where
+        K: core::borrow::Borrow<Q>
+    {
+        match (self.vec.first(), self.vec.last()) {
+            (Some(first), Some(last)) => {
+                if key < first.0.borrow() || last.0.borrow() < key {
+                    return None;
+                }
+            }
+
+            _ => { return None; }
+        }
+
+        self.vec
+            .binary_search_by(|entry| entry.0.borrow().cmp(key))
+            .ok()
+            .map(|index| &self.vec[index].1)
+    }
+}
+
+impl<K: Ord, V, M: MergeOperator<V>> Static for MergeUnit<K, V, M> {
+    fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    fn merge_with(self, other: Self) -> Self {
+        let (older, newer) = if self.max_seq < other.max_seq {
+            (self, other)
+        } else {
+            (other, self)
+        };
+
+        let mut result = MergeUnit {
+            vec: Vec::new(),
+            min_seq: older.min_seq.min(newer.min_seq),
+            max_seq: newer.max_seq,
+            writes: older.writes + newer.writes,
+            operator: PhantomData,
+        };
+
+        result.vec = merge_vecs_with(older.vec, newer.vec, |x, y| {
+            Some(combine::<K, V, M>(x, y))
+        });
+
+        // There is nothing older to remove.
+        if result.is_complete() {
+            result.vec.retain(|entry| entry.1.is_some());
+        }
+
+        result
+    }
+}
+
+impl<K: Ord, V, M: MergeOperator<V>> Singleton for MergeUnit<K, V, M> {
+    type Item = (u64, SVPair<K, Operand<V>>);
+
+    fn singleton((seq, entry): Self::Item) -> Self {
+        MergeUnit {
+            vec: vec![entry],
+            min_seq: seq,
+            max_seq: seq,
+            writes: 1,
+            operator: PhantomData,
+        }
+    }
+}
+
+impl<K: Ord, V, M: MergeOperator<V>> Insert for MergeUnit<K, V, M> {
+    /// Combines the write with an older one for the same key if present.
+    fn insert(&mut self, (seq, entry): Self::Item) {
+        self.min_seq = self.min_seq.min(seq);
+        self.max_seq = self.max_seq.max(seq);
+        self.writes += 1;
+
+        match self.vec.binary_search(&entry) {
+            Ok(index) => {
+                let SVPair(key, newer) = entry;
+                let slot = &mut self.vec[index];
+                let older = slot.1.take();
+
+                *slot = SVPair(key, combine_values::<V, M>(older, newer));
+            }
+
+            Err(index) => { self.vec.insert(index, entry); }
+        }
+    }
+}
+
+
+impl<K: Ord, V, M: MergeOperator<V>> SVMergeMap<K, V, M> {
+    /// An empty map.
+    pub fn new() -> Self {
+        SVMergeMap {
+            dynamic: Buffered::new(),
+            seq: 0,
+        }
+    }
+
+    /// The number of stored entries including the pending removals.
+    pub fn stored_len(&self) -> usize {
+        self.dynamic.len()
+    }
+
+    fn write(&mut self, key: K, value: Option<Operand<V>>) {
+        let seq = self.seq;
+        self.seq += 1;
+
+        self.dynamic.insert((seq, SVPair(key, value)));
+    }
+
+    /// Replaces the value without searching for the old one.
+    pub fn insert(&mut self, key: K, value: V) {
+        self.write(key, Some(Operand::Put(value)));
+    }
+
+    /// Combines the value with `operand` without searching for the value.
+    ///
+    /// An absent value is treated as if the operand itself was inserted.
+    pub fn merge(&mut self, key: K, operand: V) {
+        self.write(key, Some(Operand::Merge(operand)));
+    }
+
+    /// Removes the value without searching for it.
+    pub fn remove(&mut self, key: K) {
+        self.write(key, None);
+    }
+
+    /// Folds all the writes for the key.
+    ///
+    /// Returns the value or `None` if the key is absent or removed.
+    pub fn get<Q: Ord + ?Sized>(&self, key: &Q) -> Option<V> where
+        K: core::borrow::Borrow<Q>,
+        V: Clone
+    {
+        // The newer operands already combined
+        let mut pending: Option<V> = None;
+
+        let apply = |older: V, pending: Option<V>| match pending {
+            None => older,
+            Some(newer) => M::merge(older, newer),
+        };
+
+        // From the newest unit to the oldest one
+        for unit in self.dynamic.units() {
+            match unit.find(key) {
+                None => {}
+                Some(None) => { return pending; }
+
+                Some(Some(Operand::Put(value))) => {
+                    return Some(apply(value.clone(), pending));
+                }
+
+                Some(Some(Operand::Merge(delta))) => {
+                    pending = Some(apply(delta.clone(), pending));
+                }
+            }
+        }
+
+        pending
+    }
+
+    /// Returns `true` if the key is present.
+    ///
+    /// Unlike [`get`](SVMergeMap::get) checks only the newest write.
+    pub fn contains_key<Q: Ord + ?Sized>(&self, key: &Q) -> bool where
+        K: core::borrow::Borrow<Q>
+    {
+        self.dynamic.units()
+            .find_map(|unit| unit.find(key))
+            .map_or(false, |value| value.is_some())
+    }
+
+    /// Merges all the units into a single one combining all the writes.
+    pub fn compact(&mut self) {
+        let dynamic = core::mem::take(&mut self.dynamic);
+
+        if let Some(mut unit) = dynamic.try_collect() {
+            // A single unit might have not been merged at all.
+            unit.vec.retain(|entry| entry.1.is_some());
+
+            if !unit.vec.is_empty() {
+                self.dynamic.add_unit(unit);
+            }
+        }
+    }
+
+    /// Removes all elements from the map.
+    pub fn clear(&mut self) {
+        self.dynamic.clear();
+        self.seq = 0;
+    }
+}
+
+impl<K: Ord, V, M: MergeOperator<V>> Default for SVMergeMap<K, V, M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
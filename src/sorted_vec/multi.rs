@@ -0,0 +1,574 @@
+//! Containers with duplicate keys.
+
+use crate::*;
+use crate::buffered::Buffered;
+use super::{ SortedVec, merge_vecs_with };
+use super::iter::{ Merge, MergeItem, unit_range };
+use alloc::vec;
+use core::borrow::Borrow;
+use core::ops::{ Bound, Range };
+
+
+/// Bounds of the elements with the key in a sorted unit.
+fn equal_range<T, K, Q, G>(vec: &[T], key: &Q, get_key: G) -> Range<usize> where
+    K: Borrow<Q>,
+    Q: Ord + ?Sized,
+    G: Fn(&T) -> &K,
+{
+    unit_range(vec, &(Bound::Included(key), Bound::Included(key)), get_key)
+}
+
+
+/// An associative array with multiple values per key.
+///
+/// Insertions are blind: the values with equal keys are simply stored
+/// separately. The values for a key are yielded in an unspecified order.
+/// Removals take time linear in the unit size.
+///
+/// ```
+/// # #[cfg(feature="sorted_vec")] {
+/// use dynamization::sorted_vec::SVMultiMap;
+///
+/// let mut multimap = SVMultiMap::new();
+///
+/// multimap.insert("a", 1);
+/// multimap.insert("b", 2);
+/// multimap.insert("a", 3);
+///
+/// let mut values: Vec<_> = multimap.get_all("a").collect();
+/// values.sort();
+///
+/// assert_eq!(values, [&1, &3]);
+/// assert_eq!(multimap.count("b"), 1);
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct SVMultiMap<K, V, S = strategy::Binary> {
+    dynamic: Buffered<SortedVec<MultiPair<K, V>>, S>,
+    len: usize,
+}
+
+/// A key-value pair ordered by key.
+#[derive(Clone, Debug)]
+struct MultiPair<K, V>(K, V);
+
+impl<K: Ord, V> Ord for MultiPair<K, V> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl<K: Ord, V> PartialOrd for MultiPair<K, V> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord, V> PartialEq for MultiPair<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<K: Ord, V> Eq for MultiPair<K, V> {}
+
+impl<'a, K: Ord, V> MergeItem for &'a MultiPair<K, V> {
+    type Key = K;
+    type Output = (&'a K, &'a V);
+
+    fn key(&self) -> &K {
+        &self.0
+    }
+
+    fn into_output(self) -> Option<Self::Output> {
+        Some((&self.0, &self.1))
+    }
+}
+
+
+impl<K: Ord, V> SVMultiMap<K, V> {
+    /// Uses the [`Binary`](strategy::Binary) strategy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses a specified strategy.
+    pub fn with_strategy<S: Strategy>() -> SVMultiMap<K, V, S> {
+        SVMultiMap::default()
+    }
+}
+
+impl<K: Ord, V, S: Strategy> Default for SVMultiMap<K, V, S> {
+    fn default() -> Self {
+        SVMultiMap {
+            dynamic: Buffered::new(),
+            len: 0,
+        }
+    }
+}
+
+
+impl<K: Ord, V, S: Strategy> SVMultiMap<K, V, S> {
+    /// Returns the number of key-value pairs currently stored.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `self.len() == 0`.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Adds a key-value pair without searching for the key.
+    pub fn insert(&mut self, key: K, value: V) {
+        self.dynamic.insert(MultiPair(key, value));
+        self.len += 1;
+    }
+
+    /// Iterator over all the values for the key.
+    pub fn get_all<'a, Q: Ord + ?Sized>(&'a self, key: &Q) -> SVMultiMapGetAll<'a, K, V> where
+        K: Borrow<Q>
+    {
+        let slices: Vec<&'a [MultiPair<K, V>]> = self.dynamic.units().filter_map(|unit| {
+            let range = equal_range(&unit.vec, key, |pair| &pair.0);
+
+            if range.is_empty() { return None; }
+
+            Some(&unit.vec[range])
+        }).collect();
+
+        SVMultiMapGetAll {
+            slices: slices.into_iter(),
+            current: [].iter(),
+        }
+    }
+
+    /// Returns the number of values for the key.
+    pub fn count<Q: Ord + ?Sized>(&self, key: &Q) -> usize where
+        K: Borrow<Q>
+    {
+        self.dynamic.units().map(|unit| {
+            equal_range(&unit.vec, key, |pair| &pair.0).len()
+        }).sum()
+    }
+
+    /// Returns `true` if there is a value for the key.
+    pub fn contains_key<Q: Ord + ?Sized>(&self, key: &Q) -> bool where
+        K: Borrow<Q>
+    {
+        self.dynamic.units().any(|unit| {
+            !equal_range(&unit.vec, key, |pair| &pair.0).is_empty()
+        })
+    }
+
+    /// Removes one of the values for the key (unspecified which one).
+    ///
+    /// Takes time linear in the size of its unit.
+    pub fn remove_one<Q: Ord + ?Sized>(&mut self, key: &Q) -> Option<V> where
+        K: Borrow<Q>
+    {
+        let (unit, index) = self.dynamic.units_mut().find_map(|unit| {
+            let range = equal_range(&unit.vec, key, |pair| &pair.0);
+
+            if range.is_empty() { None } else { Some((unit, range.start)) }
+        })?;
+
+        let MultiPair(_, value) = unit.vec.remove(index);
+
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Removes all the values for the key and returns them.
+    ///
+    /// Takes time linear in the size of the units containing the key.
+    pub fn remove_all<Q: Ord + ?Sized>(&mut self, key: &Q) -> Vec<V> where
+        K: Borrow<Q>
+    {
+        let mut result = Vec::new();
+
+        for unit in self.dynamic.units_mut() {
+            let range = equal_range(&unit.vec, key, |pair| &pair.0);
+
+            result.extend(unit.vec.drain(range).map(|MultiPair(_, value)| value));
+        }
+
+        self.len -= result.len();
+        result
+    }
+
+    /// Removes all elements.
+    pub fn clear(&mut self) {
+        self.dynamic.clear();
+        self.len = 0;
+    }
+
+    /// Iterator over `(&key, &value)` pairs sorted by key.
+    pub fn iter(&self) -> SVMultiMapIter<'_, K, V> {
+        SVMultiMapIter {
+            merge: Merge::new(self.dynamic.units().map(|unit| unit.vec.iter())),
+            len: self.len,
+        }
+    }
+}
+
+impl<K: Ord, V, S: Strategy> core::iter::FromIterator<(K, V)> for SVMultiMap<K, V, S> {
+    fn from_iter<I: IntoIterator<Item=(K, V)>>(iter: I) -> Self {
+        let sv = iter
+            .into_iter()
+            .map(|(k, v)| MultiPair(k, v))
+            .collect::<SortedVec<_>>();
+
+        let len = sv.vec.len();
+        let mut dynamic = Buffered::new();
+
+        if len > 0 {
+            dynamic.add_unit(sv);
+        }
+
+        SVMultiMap { dynamic, len }
+    }
+}
+
+impl<'a, K: Ord, V, S: Strategy> IntoIterator for &'a SVMultiMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = SVMultiMapIter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+
+/// Iterator over the values for a key of an [`SVMultiMap`].
+pub struct SVMultiMapGetAll<'a, K, V> {
+    slices: vec::IntoIter<&'a [MultiPair<K, V>]>,
+    current: core::slice::Iter<'a, MultiPair<K, V>>,
+}
+
+impl<'a, K, V> Iterator for SVMultiMapGetAll<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(pair) = self.current.next() {
+                return Some(&pair.1);
+            }
+
+            self.current = self.slices.next()?.iter();
+        }
+    }
+}
+
+impl<'a, K, V> core::iter::FusedIterator for SVMultiMapGetAll<'a, K, V> {}
+
+
+/// Iterator over `(&key, &value)` pairs of an [`SVMultiMap`] sorted by key.
+pub struct SVMultiMapIter<'a, K, V> {
+    merge: Merge<core::slice::Iter<'a, MultiPair<K, V>>>,
+    len: usize,
+}
+
+impl<'a, K: Ord, V> Iterator for SVMultiMapIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let kv = self.merge.next_front()?;
+        self.len -= 1;
+        Some(kv)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, K: Ord, V> DoubleEndedIterator for SVMultiMapIter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let kv = self.merge.next_back()?;
+        self.len -= 1;
+        Some(kv)
+    }
+}
+
+impl<'a, K: Ord, V> ExactSizeIterator for SVMultiMapIter<'a, K, V> {}
+
+impl<'a, K: Ord, V> core::iter::FusedIterator for SVMultiMapIter<'a, K, V> {}
+
+
+
+/// A multiset storing the count of each distinct element.
+///
+/// Insertions don't search the units: equal elements are collapsed into
+/// a single counter when the units are merged.
+///
+/// ```
+/// # #[cfg(feature="sorted_vec")] {
+/// use dynamization::sorted_vec::SVBag;
+///
+/// let bag: SVBag<_> = "abracadabra".chars().collect();
+///
+/// assert_eq!(bag.count(&'a'), 5);
+/// assert_eq!(bag.len(), 11);
+/// assert!(bag.iter().eq(vec![(&'a', 5), (&'b', 2), (&'c', 1), (&'d', 1), (&'r', 2)]));
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct SVBag<T, S = strategy::Binary> {
+    dynamic: Buffered<BagUnit<T>, S>,
+    len: usize,
+}
+
+/// An element with its count ordered by the element.
+#[derive(Clone, Debug)]
+struct Counted<T>(T, usize);
+
+impl<T: Ord> Ord for Counted<T> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl<T: Ord> PartialOrd for Counted<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> PartialEq for Counted<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: Ord> Eq for Counted<T> {}
+
+impl<'a, T: Ord> MergeItem for &'a Counted<T> {
+    type Key = T;
+    type Output = (&'a T, usize);
+
+    fn key(&self) -> &T {
+        &self.0
+    }
+
+    fn into_output(self) -> Option<Self::Output> {
+        Some((&self.0, self.1))
+    }
+}
+
+/// A sorted vector of distinct elements with their counts.
+#[derive(Clone, Debug)]
+struct BagUnit<T> {
+    vec: Vec<Counted<T>>,
+}
+
+impl<T: Ord> Static for BagUnit<T> {
+    fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    fn merge_with(self, other: Self) -> Self {
+        BagUnit {
+            vec: merge_vecs_with(self.vec, other.vec, |x, y| {
+                Some(Counted(x.0, x.1 + y.1))
+            }),
+        }
+    }
+}
+
+impl<T: Ord> Singleton for BagUnit<T> {
+    type Item = T;
+
+    fn singleton(item: T) -> Self {
+        BagUnit { vec: vec![Counted(item, 1)] }
+    }
+}
+
+impl<T: Ord> Insert for BagUnit<T> {
+    fn insert(&mut self, item: T) {
+        match self.vec.binary_search_by(|x| x.0.cmp(&item)) {
+            Ok(index) => { self.vec[index].1 += 1; }
+            Err(index) => { self.vec.insert(index, Counted(item, 1)); }
+        }
+    }
+}
+
+impl<T> BagUnit<T> {
+    fn find<Q: Ord + ?Sized>(&self, value: &Q) -> Option<usize> where
+        T: Borrow<Q>
+    {
+        self.vec.binary_search_by(|x| x.0.borrow().cmp(value)).ok()
+    }
+}
+
+
+impl<T: Ord> SVBag<T> {
+    /// Uses the [`Binary`](strategy::Binary) strategy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses a specified strategy.
+    pub fn with_strategy<S: Strategy>() -> SVBag<T, S> {
+        SVBag::default()
+    }
+}
+
+impl<T: Ord, S: Strategy> Default for SVBag<T, S> {
+    fn default() -> Self {
+        SVBag {
+            dynamic: Buffered::new(),
+            len: 0,
+        }
+    }
+}
+
+
+impl<T: Ord, S: Strategy> SVBag<T, S> {
+    /// Returns the number of elements including the duplicates.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `self.len() == 0`.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Adds an element without searching for the equal ones.
+    pub fn insert(&mut self, value: T) {
+        self.dynamic.insert(value);
+        self.len += 1;
+    }
+
+    /// Returns the number of elements equal to the value.
+    pub fn count<Q: Ord + ?Sized>(&self, value: &Q) -> usize where
+        T: Borrow<Q>
+    {
+        self.dynamic.units()
+            .filter_map(|unit| unit.find(value).map(|index| unit.vec[index].1))
+            .sum()
+    }
+
+    /// Returns `true` if there is an element equal to the value.
+    pub fn contains<Q: Ord + ?Sized>(&self, value: &Q) -> bool where
+        T: Borrow<Q>
+    {
+        self.dynamic.units().any(|unit| unit.find(value).is_some())
+    }
+
+    /// Removes one element equal to the value.
+    ///
+    /// Returns `false` if there are none. Takes time linear in the unit size
+    /// when the last one of the counted elements is removed from a unit.
+    pub fn remove_one<Q: Ord + ?Sized>(&mut self, value: &Q) -> bool where
+        T: Borrow<Q>
+    {
+        let found = self.dynamic.units_mut().find_map(|unit| {
+            unit.find(value).map(|index| (unit, index))
+        });
+
+        match found {
+            None => false,
+
+            Some((unit, index)) => {
+                unit.vec[index].1 -= 1;
+
+                if unit.vec[index].1 == 0 {
+                    unit.vec.remove(index);
+                }
+
+                self.len -= 1;
+                true
+            }
+        }
+    }
+
+    /// Removes all the elements equal to the value.
+    ///
+    /// Returns the number of the elements removed.
+    pub fn remove_all<Q: Ord + ?Sized>(&mut self, value: &Q) -> usize where
+        T: Borrow<Q>
+    {
+        let mut count = 0;
+
+        for unit in self.dynamic.units_mut() {
+            if let Some(index) = unit.find(value) {
+                count += unit.vec.remove(index).1;
+            }
+        }
+
+        self.len -= count;
+        count
+    }
+
+    /// Removes all elements.
+    pub fn clear(&mut self) {
+        self.dynamic.clear();
+        self.len = 0;
+    }
+
+    /// Iterator over the distinct elements with their counts in sorted order.
+    pub fn iter(&self) -> SVBagIter<'_, T> {
+        SVBagIter {
+            merge: Merge::new(self.dynamic.units().map(|unit| unit.vec.iter())),
+            pending: None,
+        }
+    }
+}
+
+impl<T: Ord, S: Strategy> core::iter::FromIterator<T> for SVBag<T, S> {
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        let mut vec: Vec<T> = iter.into_iter().collect();
+        vec.sort();
+
+        let len = vec.len();
+        let mut counted: Vec<Counted<T>> = Vec::new();
+
+        for value in vec {
+            match counted.last_mut() {
+                Some(last) if last.0 == value => { last.1 += 1; }
+                _ => { counted.push(Counted(value, 1)); }
+            }
+        }
+
+        let mut dynamic = Buffered::new();
+
+        if len > 0 {
+            dynamic.add_unit(BagUnit { vec: counted });
+        }
+
+        SVBag { dynamic, len }
+    }
+}
+
+
+/// Iterator over the distinct elements of an [`SVBag`] with their counts.
+pub struct SVBagIter<'a, T> {
+    merge: Merge<core::slice::Iter<'a, Counted<T>>>,
+    pending: Option<(&'a T, usize)>,
+}
+
+impl<'a, T: Ord> Iterator for SVBagIter<'a, T> {
+    type Item = (&'a T, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (value, mut count) = self.pending.take().or_else(|| self.merge.next_front())?;
+
+        // Equal elements from different units are adjacent.
+        loop {
+            match self.merge.next_front() {
+                Some((other, other_count)) if other == value => {
+                    count += other_count;
+                }
+
+                next => {
+                    self.pending = next;
+                    return Some((value, count));
+                }
+            }
+        }
+    }
+}
+
+impl<'a, T: Ord> core::iter::FusedIterator for SVBagIter<'a, T> {}
@@ -0,0 +1,64 @@
+//! Prefix scans over [`SVMap`] keys.
+
+use crate::*;
+use super::{ SVMap, SVMapRange, Filter };
+use core::borrow::Borrow;
+use core::ops::Bound;
+
+
+/// Keys ordered lexicographically: all the keys starting with a prefix
+/// follow it in sorted order.
+pub trait KeyPrefix: Ord {
+    /// Returns `true` if `prefix` is a prefix of `self`.
+    fn has_prefix(&self, prefix: &Self) -> bool;
+}
+
+impl KeyPrefix for str {
+    fn has_prefix(&self, prefix: &Self) -> bool {
+        self.starts_with(prefix)
+    }
+}
+
+impl<T: Ord> KeyPrefix for [T] {
+    fn has_prefix(&self, prefix: &Self) -> bool {
+        self.starts_with(prefix)
+    }
+}
+
+
+impl<K: Ord, V, S: Strategy, F: Filter<K>> SVMap<K, V, S, F> {
+    /// Iterator over `(&key, &value)` pairs with the keys starting with
+    /// `prefix` sorted by key.
+    ///
+    /// The keys with the prefix form a contiguous part of each unit found
+    /// by two binary searches.
+    ///
+    /// ```
+    /// # #[cfg(feature="sorted_vec")] {
+    /// use dynamization::sorted_vec::SVMap;
+    ///
+    /// let map: SVMap<String, i32> = ["car", "cart", "cat", "dog"]
+    ///     .iter()
+    ///     .map(|word| (word.to_string(), word.len() as i32))
+    ///     .collect();
+    ///
+    /// let keys: Vec<&String> = map.prefix("car").map(|(key, _)| key).collect();
+    /// assert_eq!(keys, ["car", "cart"]);
+    /// # }
+    /// ```
+    pub fn prefix<P: KeyPrefix + ?Sized>(&self, prefix: &P) -> SVMapRange<'_, K, V> where
+        K: Borrow<P>
+    {
+        let fences = (Bound::Included(prefix), Bound::Unbounded);
+
+        self.range_by(&fences, |slice| {
+            let start = slice.partition_point(|entry| entry.0.borrow() < prefix);
+
+            let len = slice[start..].partition_point(|entry| {
+                entry.0.borrow().has_prefix(prefix)
+            });
+
+            start..start + len
+        })
+    }
+}
@@ -0,0 +1,154 @@
+//! Order statistics on [`SVMap`] keys.
+//!
+//! Ranks are decomposable: the rank of a key is the sum of its positions
+//! in all the units minus the removed entries before these positions.
+//! Selection is a multi-unit search shared with [`SVSet`](super::SVSet).
+
+use crate::*;
+use super::{ SVMap, SVMapUnit, Filter };
+use core::borrow::Borrow;
+
+
+impl<K, V, F> SVMapUnit<K, V, F> {
+    /// The number of live entries in `vec[..end]`.
+    ///
+    /// Scans the shorter part of the unit if there are tombstones.
+    fn live_before(&self, end: usize) -> usize {
+        let dead_before: usize = self.dead
+            .iter()
+            .map(|span| (span.start.min(end)..span.end.min(end)).len())
+            .sum();
+
+        if self.free_count == 0 {
+            return end - dead_before;
+        }
+
+        // The tombstones in the live slices of `vec[range]`
+        let free_in = |range: core::ops::Range<usize>| -> usize {
+            super::live_spans(&self.dead, self.vec.len())
+                .map(|span| span.start.max(range.start)..span.end.min(range.end))
+                .filter(|span| span.start < span.end)
+                .map(|span| self.vec[span].iter().filter(|entry| entry.1.is_none()).count())
+                .sum()
+        };
+
+        let free_before = if 2 * end <= self.vec.len() {
+            free_in(0..end)
+        } else {
+            self.free_count - free_in(end..self.vec.len())
+        };
+
+        end - dead_before - free_before
+    }
+}
+
+
+impl<K: Ord, V, S: Strategy, F: Filter<K>> SVMap<K, V, S, F> {
+    /// The number of keys less than `key`.
+    ///
+    /// Takes `O(log N)` time per unit (plus a scan of the units having 
+    /// removed entries).
+    pub fn rank<Q: Ord + ?Sized>(&self, key: &Q) -> usize where
+        K: Borrow<Q>
+    {
+        self.dynamic.units().map(|unit| {
+            unit.live_before(unit.vec.partition_point(|entry| entry.0.borrow() < key))
+        }).sum()
+    }
+
+    /// Returns the `n`-th smallest key (counting from zero) or `None` 
+    /// if `n >= self.len()`.
+    ///
+    /// A multi-unit search: takes `O(log N)` rank computations per unit.
+    pub fn select(&self, n: usize) -> Option<&K> {
+        self.nth(n).map(|(key, _)| key)
+    }
+
+    /// Returns the `n`-th smallest key (counting from zero) with its value
+    /// or `None` if `n >= self.len()`.
+    ///
+    /// See [`select`](SVMap::select).
+    pub fn nth(&self, n: usize) -> Option<(&K, &V)> {
+        if n >= self.len {
+            return None;
+        }
+
+        let units: Vec<&SVMapUnit<K, V, F>> = self.dynamic.units().collect();
+
+        let (unit_no, index) = select_location(
+            &units,
+            n,
+            |unit| &unit.vec,
+            |entry| &entry.0,
+            |unit, end| unit.live_before(end),
+            |unit, index| unit.vec[index].1.is_some() && !unit.is_dead(index),
+        )?;
+
+        let entry = &units[unit_no].vec[index];
+
+        entry.1.as_ref().map(|value| (&entry.0, value))
+    }
+}
+
+
+/// Finds the `n`-th smallest live element of the units by a multi-unit
+/// search.
+///
+/// Returns the number of its unit in `units` and its index in the unit.
+pub(super) fn select_location<U, T, K: Ord>(
+    units: &[U],
+    n: usize,
+    vec: impl Fn(&U) -> &[T],
+    key: impl Fn(&T) -> &K,
+    live_before: impl Fn(&U, usize) -> usize,
+    is_live: impl Fn(&U, usize) -> bool,
+) -> Option<(usize, usize)> {
+    // The element is in one of `vec(units[u])[bounds[u]]`.
+    let mut bounds: Vec<(usize, usize)> = units
+        .iter()
+        .map(|unit| (0, vec(unit).len()))
+        .collect();
+
+    loop {
+        // The widest range is halved on each step.
+        let (unit_no, &(start, end)) = bounds
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, &(start, end))| end - start)?;
+
+        if start == end {
+            return None;
+        }
+
+        let index = (start + end) / 2;
+        let pivot = &vec(&units[unit_no])[index];
+
+        let mut positions: Vec<usize> = units.iter().map(|unit| {
+            vec(unit).partition_point(|x| key(x) < key(pivot))
+        }).collect();
+
+        let rank: usize = units
+            .iter()
+            .zip(&positions)
+            .map(|(unit, &position)| live_before(unit, position))
+            .sum();
+
+        if rank == n && is_live(&units[unit_no], index) {
+            return Some((unit_no, index));
+        }
+
+        if rank > n {
+            for (bound, &position) in bounds.iter_mut().zip(&positions) {
+                bound.1 = bound.1.min(position);
+            }
+        } else {
+            // Only this unit can contain the pivot unless it's a removed 
+            // copy (then a live one is not less than the pivot).
+            positions[unit_no] = index + 1;
+
+            for (bound, &position) in bounds.iter_mut().zip(&positions) {
+                bound.0 = bound.0.max(position);
+            }
+        }
+    }
+}
@@ -0,0 +1,151 @@
+//! Dropping the removed entries of [`SVMap`] and
+//! [`SVStrMap`](super::SVStrMap).
+//!
+//! Merges of units drop the removed entries on their own, but the larger
+//! units are merged rarely: a [`RebuildPolicy`] decides when to drop them
+//! from such units.
+
+use crate::*;
+use super::{ SVMap, SVMapUnit, Filter };
+
+
+/// When [`SVMap`] or [`SVStrMap`](super::SVStrMap) drops the removed 
+/// entries (tombstones) from its units.
+///
+/// Checked after each removal. See [`SVMap::set_rebuild_policy`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RebuildPolicy {
+    /// Rebuilds the whole map into a single unit when the removed entries
+    /// make up more than the fraction of all the stored ones.
+    ///
+    /// Small maps (up to 16 items) are never rebuilt. The default policy
+    /// is `Fraction(0.5)`: the removed entries outnumber the items.
+    Fraction(f64),
+
+    /// Rebuilds the whole map into a single unit when there are more
+    /// removed entries than the number.
+    Count(usize),
+
+    /// Compacts each unit in which the removed entries make up more than
+    /// the fraction of its entries.
+    ///
+    /// Takes time linear in the size of the compacted units only and
+    /// leaves the other units intact.
+    PerUnit(f64),
+
+    /// Relies on the merges only.
+    Never,
+}
+
+impl Default for RebuildPolicy {
+    fn default() -> Self {
+        RebuildPolicy::Fraction(0.5)
+    }
+}
+
+/// Containers up to this size are never rebuilt by [`RebuildPolicy::Fraction`].
+const REBUILD_THRESHOLD: usize = 16;
+
+impl RebuildPolicy {
+    /// Returns `true` if a container of `len` items among `stored` entries
+    /// should be rebuilt into a single unit.
+    pub(super) fn rebuilds(self, len: usize, stored: usize) -> bool {
+        let garbage = stored - len;
+
+        match self {
+            RebuildPolicy::Fraction(fraction) => {
+                len > REBUILD_THRESHOLD && garbage as f64 > fraction * stored as f64
+            }
+
+            RebuildPolicy::Count(count) => garbage > count,
+            RebuildPolicy::PerUnit(_) | RebuildPolicy::Never => false,
+        }
+    }
+
+    /// Returns `true` if a unit with `garbage` removed entries among 
+    /// `stored` ones should be compacted.
+    pub(super) fn compacts(self, garbage: usize, stored: usize) -> bool {
+        match self {
+            RebuildPolicy::PerUnit(fraction) => garbage as f64 > fraction * stored as f64,
+            _ => false,
+        }
+    }
+}
+
+
+impl<K, V, F> SVMapUnit<K, V, F> {
+    /// The number of removed entries including the ones covered by range
+    /// tombstones.
+    fn garbage(&self) -> usize {
+        self.free_count + self.dead.iter().map(|span| span.len()).sum::<usize>()
+    }
+}
+
+impl<K: Ord, V, F: Filter<K>> SVMapUnit<K, V, F> {
+    /// Drops the removed entries and rebuilds the filter.
+    fn compact(&mut self) {
+        self.purge();
+
+        let mut vec = core::mem::take(&mut self.vec);
+        vec.retain(|entry| entry.1.is_some());
+
+        *self = Self::from_vec(vec, 0);
+    }
+}
+
+
+impl<K: Ord, V, S: Strategy, F: Filter<K>> SVMap<K, V, S, F> {
+    /// The policy for dropping the removed entries.
+    pub fn rebuild_policy(&self) -> RebuildPolicy {
+        self.policy
+    }
+
+    /// Sets the policy for dropping the removed entries and applies it.
+    ///
+    /// ```
+    /// # #[cfg(feature="sorted_vec")] {
+    /// use dynamization::sorted_vec::{ SVMap, RebuildPolicy };
+    ///
+    /// let mut map: SVMap<_, _> = (0..1000).map(|x| (x, x)).collect();
+    ///
+    /// // Compact a unit when a quarter of it is removed.
+    /// map.set_rebuild_policy(RebuildPolicy::PerUnit(0.25));
+    ///
+    /// for x in 0..500 {
+    ///     map.remove(&x);
+    /// }
+    ///
+    /// assert_eq!(map.len(), 500);
+    /// # }
+    /// ```
+    pub fn set_rebuild_policy(&mut self, policy: RebuildPolicy) {
+        self.policy = policy;
+        self.rebuild_if_sparse();
+    }
+
+    /// Drops the removed entries if the policy says so.
+    pub(super) fn rebuild_if_sparse(&mut self) {
+        let policy = self.policy;
+
+        if let RebuildPolicy::PerUnit(_) = policy {
+            for unit in self.dynamic.units_mut() {
+                if policy.compacts(unit.garbage(), unit.vec.len()) {
+                    unit.compact();
+                }
+            }
+        }
+
+        if policy.rebuilds(self.len, self.dynamic.len()) {
+            self.rebuild();
+        }
+    }
+
+    /// Collects all the items into a single unit.
+    fn rebuild(&mut self) {
+        let policy = self.policy;
+        let map = core::mem::take(self);
+
+        *self = map.into_iter().collect();
+        self.policy = policy;
+    }
+}
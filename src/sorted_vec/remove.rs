@@ -0,0 +1,295 @@
+//! Bulk removals from [`SVMap`].
+//!
+//! [`retain`](SVMap::retain) leaves tombstones and
+//! [`extract_if`](SVMap::extract_if) compacts the units it visits: both
+//! rebuild the map at most once. [`remove_range`](SVMap::remove_range)
+//! covers a span of each large unit with a range tombstone without touching
+//! its entries: the span is dropped when the unit is merged.
+
+use crate::*;
+use super::{ SVMap, SVPair, SVMapUnit, Filter, live_spans };
+use super::iter::{ check_range, unit_range };
+use core::borrow::Borrow;
+use core::ops::{ Range, RangeBounds };
+
+
+impl<K, V, F> SVMapUnit<K, V, F> {
+    /// Covers `span` of `vec` with a range tombstone.
+    ///
+    /// Returns the number of the live entries covered.
+    fn kill(&mut self, mut span: Range<usize>) -> usize {
+        let mut covered = 0;
+        let mut free = 0;
+
+        for part in live_spans(&self.dead, self.vec.len()) {
+            let part = part.start.max(span.start)..part.end.min(span.end);
+
+            if part.start < part.end {
+                covered += part.len();
+
+                if self.free_count > 0 {
+                    free += self.vec[part].iter().filter(|entry| entry.1.is_none()).count();
+                }
+            }
+        }
+
+        // The tombstones are covered as well.
+        self.free_count -= free;
+
+        // Joins the overlapping and the adjacent spans.
+        self.dead.retain(|other| {
+            if other.start <= span.end && span.start <= other.end {
+                span = span.start.min(other.start)..span.end.max(other.end);
+                false
+            } else {
+                true
+            }
+        });
+
+        let index = self.dead.partition_point(|other| other.end < span.start);
+        self.dead.insert(index, span);
+
+        covered - free
+    }
+}
+
+
+impl<K: Ord, V, S: Strategy, F: Filter<K>> SVMap<K, V, S, F> {
+    /// Retains only the pairs for which `f` returns `true`.
+    ///
+    /// The pairs are visited unit by unit in no particular order.
+    /// The removed ones leave tombstones: the map is rebuilt at most once
+    /// afterwards.
+    pub fn retain<G: FnMut(&K, &mut V) -> bool>(&mut self, mut f: G) {
+        let mut removed = 0;
+
+        for unit in self.dynamic.units_mut() {
+            let mut free = 0;
+
+            for slice in unit.live_slices_mut() {
+                for SVPair(key, value) in slice {
+                    if value.as_mut().map_or(false, |value| !f(key, value)) {
+                        *value = None;
+                        free += 1;
+                    }
+                }
+            }
+
+            unit.free_count += free;
+            removed += free;
+        }
+
+        self.len -= removed;
+        self.rebuild_if_sparse();
+    }
+
+    /// A lazy iterator removing the pairs for which `pred` returns `true`
+    /// and yielding them.
+    ///
+    /// The pairs are visited unit by unit in no particular order. Each unit
+    /// is compacted on the way, so no tombstones are left. The pairs not
+    /// visited before the iterator is dropped stay in the map.
+    ///
+    /// ```
+    /// # #[cfg(feature="sorted_vec")] {
+    /// use dynamization::sorted_vec::SVMap;
+    ///
+    /// let mut map: SVMap<_, _> = (0..100).map(|x| (x, x)).collect();
+    ///
+    /// let mut odd: Vec<_> = map.extract_if(|k, _| k % 2 == 1).collect();
+    /// odd.sort();
+    ///
+    /// assert_eq!(odd.len(), 50);
+    /// assert_eq!(odd[0], (1, 1));
+    /// assert_eq!(map.len(), 50);
+    /// # }
+    /// ```
+    pub fn extract_if<P>(&mut self, pred: P) -> SVMapExtractIf<'_, K, V, S, F, P> where
+        P: FnMut(&K, &mut V) -> bool
+    {
+        SVMapExtractIf {
+            map: self,
+            unit_no: 0,
+            taken: false,
+            rest: Vec::new().into_iter(),
+            kept: Vec::new(),
+            pred,
+        }
+    }
+
+    /// Removes the pairs with the keys in `range`. Returns the number
+    /// of the pairs removed.
+    ///
+    /// Small units (not larger than the write buffer) are updated at once.
+    /// A span of each larger unit is covered with a range tombstone in
+    /// `O(log N)` time (plus a scan if the unit has tombstones): the entries
+    /// are dropped when the unit is merged. The map is rebuilt at most once.
+    ///
+    /// # Panics
+    ///
+    /// Panics in the same cases as [`range`](SVMap::range).
+    ///
+    /// ```
+    /// # #[cfg(feature="sorted_vec")] {
+    /// use dynamization::sorted_vec::SVMap;
+    ///
+    /// let mut map: SVMap<_, _> = (0..1000).map(|x| (x, x)).collect();
+    ///
+    /// assert_eq!(map.remove_range(100..900), 800);
+    /// assert_eq!(map.len(), 200);
+    /// assert_eq!(map.range(90..910).count(), 20);
+    /// # }
+    /// ```
+    pub fn remove_range<Q, R>(&mut self, range: R) -> usize where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        check_range(&range, "SVMap");
+
+        let capacity = self.dynamic.capacity();
+        let mut removed = 0;
+
+        for unit in self.dynamic.units_mut() {
+            if !unit.fences_overlap(&range) { continue; }
+
+            let small = unit.vec.len() <= capacity;
+
+            if small {
+                unit.purge();
+            }
+
+            let bounds = unit_range(&unit.vec, &range, |entry| &entry.0);
+
+            if bounds.is_empty() { continue; }
+
+            if small {
+                let len = bounds.len();
+                let free = unit.vec.drain(bounds).filter(|entry| entry.1.is_none()).count();
+
+                unit.free_count -= free;
+                removed += len - free;
+            } else {
+                removed += unit.kill(bounds);
+            }
+        }
+
+        self.len -= removed;
+        self.rebuild_if_sparse();
+
+        removed
+    }
+}
+
+
+/// A lazy iterator removing the pairs of an [`SVMap`] matching
+/// a predicate. Yields `(key, value)` pairs.
+///
+/// Constructed by [`SVMap::extract_if`].
+pub struct SVMapExtractIf<'a, K: Ord, V, S: Strategy, F: Filter<K>, P> {
+    map: &'a mut SVMap<K, V, S, F>,
+
+    /// The unit being compacted (if `taken`) or the next one.
+    unit_no: usize,
+    taken: bool,
+
+    /// The entries of the unit not visited yet.
+    rest: alloc::vec::IntoIter<SVPair<K, V>>,
+    kept: Vec<SVPair<K, V>>,
+
+    pred: P,
+}
+
+impl<'a, K: Ord, V, S: Strategy, F: Filter<K>, P> SVMapExtractIf<'a, K, V, S, F, P> {
+    /// Moves the entries of the next unit out of it.
+    ///
+    /// The entries are not counted in the map length until put back,
+    /// so a leaked iterator leaves the map consistent.
+    fn take_unit(&mut self) -> bool {
+        let unit = match self.map.unit_mut(self.unit_no) {
+            Some(unit) => unit,
+            None => { return false; }
+        };
+
+        unit.purge();
+
+        let vec = core::mem::take(&mut unit.vec);
+        let live = vec.len() - unit.free_count;
+        unit.free_count = 0;
+
+        self.map.len -= live;
+        self.kept = Vec::with_capacity(vec.len());
+        self.rest = vec.into_iter();
+        self.taken = true;
+
+        true
+    }
+
+    /// Puts the kept and the remaining entries back into their unit.
+    ///
+    /// The unit filter still covers them.
+    fn restore_unit(&mut self) {
+        if !self.taken { return; }
+
+        let mut vec = core::mem::take(&mut self.kept);
+        vec.extend(&mut self.rest);
+
+        let free = vec.iter().filter(|entry| entry.1.is_none()).count();
+        self.map.len += vec.len() - free;
+
+        if let Some(unit) = self.map.unit_mut(self.unit_no) {
+            unit.vec = vec;
+            unit.free_count = free;
+        }
+
+        self.unit_no += 1;
+        self.taken = false;
+    }
+}
+
+impl<'a, K, V, S, F, P> Iterator for SVMapExtractIf<'a, K, V, S, F, P> where
+    K: Ord,
+    S: Strategy,
+    F: Filter<K>,
+    P: FnMut(&K, &mut V) -> bool,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            for SVPair(key, value) in self.rest.by_ref() {
+                match value {
+                    Some(mut value) => {
+                        if (self.pred)(&key, &mut value) {
+                            return Some((key, value));
+                        }
+
+                        self.kept.push(SVPair(key, Some(value)));
+                    }
+
+                    None => { self.kept.push(SVPair(key, None)); }
+                }
+            }
+
+            self.restore_unit();
+
+            if !self.take_unit() {
+                return None;
+            }
+        }
+    }
+}
+
+impl<'a, K, V, S, F, P> core::iter::FusedIterator for SVMapExtractIf<'a, K, V, S, F, P> where
+    K: Ord,
+    S: Strategy,
+    F: Filter<K>,
+    P: FnMut(&K, &mut V) -> bool,
+{}
+
+impl<'a, K: Ord, V, S: Strategy, F: Filter<K>, P> Drop for SVMapExtractIf<'a, K, V, S, F, P> {
+    fn drop(&mut self) {
+        self.restore_unit();
+        self.map.rebuild_if_sparse();
+    }
+}
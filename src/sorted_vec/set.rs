@@ -0,0 +1,448 @@
+//! Sorted set with set algebra.
+
+use crate::*;
+use super::{ SortedVec, SVMap, SVMapKeys, SVMapKV, SVMapRange, Entry, RebuildPolicy };
+use super::iter::check_range;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::iter::{ FusedIterator, Peekable };
+use core::ops::RangeBounds;
+
+
+/// A set based on a sorted vector.
+///
+/// Stored as an [`SVMap`](super::SVMap) with `()` values, so removals leave
+/// tombstones dropped by merges and by the [`RebuildPolicy`].
+///
+/// The set operations ([`union`](SVSet::union) etc.) are lazy and accept
+/// another `SVSet` as well as any iterator yielding references to elements
+/// in strictly increasing order.
+///
+/// ```
+/// # #[cfg(feature="sorted_vec")] {
+/// use dynamization::sorted_vec::SVSet;
+///
+/// let a: SVSet<_> = (0..10).collect();
+/// let b: SVSet<_> = (5..15).collect();
+///
+/// assert!(a.intersection(&b).eq(&[5, 6, 7, 8, 9]));
+/// assert!(a.difference(&[1, 2, 3]).eq(&[0, 4, 5, 6, 7, 8, 9]));
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct SVSet<T, S = strategy::Binary> {
+    map: SVMap<T, (), S>,
+}
+
+
+impl<T: Ord> SVSet<T> {
+    /// Uses the [`Binary`](strategy::Binary) strategy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses a specified strategy.
+    pub fn with_strategy<S: Strategy>() -> SVSet<T, S> {
+        SVSet::default()
+    }
+}
+
+impl<T: Ord, S: Strategy> Default for SVSet<T, S> {
+    fn default() -> Self {
+        SVSet { map: SVMap::default() }
+    }
+}
+
+impl<T: Ord + core::fmt::Debug, S: Strategy> core::fmt::Debug for SVSet<T, S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+
+impl<T: Ord, S: Strategy> SVSet<T, S> {
+    /// Returns the number of elements currently stored.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `self.len() == 0`.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns `true` if the set contains the value.
+    pub fn contains<Q: Ord + ?Sized>(&self, value: &Q) -> bool where
+        T: Borrow<Q>
+    {
+        self.map.contains_key(value)
+    }
+
+    /// Returns a reference to the element equal to the value if any.
+    pub fn get<Q: Ord + ?Sized>(&self, value: &Q) -> Option<&T> where
+        T: Borrow<Q>
+    {
+        self.map.get_key_value(value).map(|(x, _)| x)
+    }
+
+    /// Adds the value to the set.
+    ///
+    /// Returns `false` (and doesn't replace the element) if an equal
+    /// element is already present.
+    pub fn insert(&mut self, value: T) -> bool {
+        match self.map.entry(value) {
+            Entry::Occupied(_) => false,
+
+            Entry::Vacant(entry) => {
+                entry.insert(());
+                true
+            }
+        }
+    }
+
+    /// Removes the element equal to the value and returns it.
+    ///
+    /// Leaves a tombstone (as [`remove`](SVSet::remove) does), so
+    /// the element is cloned out of it.
+    pub fn take<Q: Ord + ?Sized>(&mut self, value: &Q) -> Option<T> where
+        T: Borrow<Q> + Clone
+    {
+        let result = self.get(value)?.clone();
+        self.map.remove(value);
+
+        Some(result)
+    }
+
+    /// Removes the element equal to the value leaving a tombstone.
+    ///
+    /// Returns `true` if it has been present.
+    pub fn remove<Q: Ord + ?Sized>(&mut self, value: &Q) -> bool where
+        T: Borrow<Q>
+    {
+        self.map.remove(value).is_some()
+    }
+
+    /// Removes all elements.
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// The policy for dropping the tombstones: see
+    /// [`SVMap::rebuild_policy`](super::SVMap::rebuild_policy).
+    pub fn rebuild_policy(&self) -> RebuildPolicy {
+        self.map.rebuild_policy()
+    }
+
+    /// Sets the policy for dropping the tombstones and applies it.
+    pub fn set_rebuild_policy(&mut self, policy: RebuildPolicy) {
+        self.map.set_rebuild_policy(policy);
+    }
+
+    /// Iterator over the elements in sorted order.
+    pub fn iter(&self) -> SVSetIter<'_, T> {
+        SVSetIter { keys: self.map.keys() }
+    }
+
+    /// Iterator over the elements in `range` in sorted order.
+    ///
+    /// # Panics
+    ///
+    /// Panics in the same cases as [`SVMap::range`](super::SVMap::range).
+    pub fn range<Q, R>(&self, range: R) -> SVSetRange<'_, T> where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+        R: RangeBounds<Q>,
+    {
+        check_range(&range, "SVSet");
+
+        SVSetRange { range: self.map.range(range) }
+    }
+
+    /// The smallest element.
+    pub fn first(&self) -> Option<&T> {
+        self.map.first_key_value().map(|(x, _)| x)
+    }
+
+    /// The largest element.
+    pub fn last(&self) -> Option<&T> {
+        self.map.last_key_value().map(|(x, _)| x)
+    }
+
+    /// The number of elements less than the value.
+    pub fn rank<Q: Ord + ?Sized>(&self, value: &Q) -> usize where
+        T: Borrow<Q>
+    {
+        self.map.rank(value)
+    }
+
+    /// Returns the `n`-th smallest element (counting from zero) or `None`
+    /// if `n >= self.len()`.
+    pub fn select(&self, n: usize) -> Option<&T> {
+        self.map.select(n)
+    }
+
+    /// Elements in `self` or in `other` (without duplicates) in sorted order.
+    ///
+    /// `other` must be sorted in strictly increasing order.
+    pub fn union<'a, I>(&'a self, other: I) -> SVSetUnion<SVSetIter<'a, T>, I::IntoIter> where
+        I: IntoIterator<Item = &'a T>
+    {
+        SVSetUnion { a: self.iter().peekable(), b: other.into_iter().peekable() }
+    }
+
+    /// Elements both in `self` and in `other` in sorted order.
+    ///
+    /// `other` must be sorted in strictly increasing order.
+    pub fn intersection<'a, I>(&'a self, other: I) -> SVSetIntersection<SVSetIter<'a, T>, I::IntoIter> where
+        I: IntoIterator<Item = &'a T>
+    {
+        SVSetIntersection { a: self.iter().peekable(), b: other.into_iter().peekable() }
+    }
+
+    /// Elements in `self` but not in `other` in sorted order.
+    ///
+    /// `other` must be sorted in strictly increasing order.
+    pub fn difference<'a, I>(&'a self, other: I) -> SVSetDifference<SVSetIter<'a, T>, I::IntoIter> where
+        I: IntoIterator<Item = &'a T>
+    {
+        SVSetDifference { a: self.iter().peekable(), b: other.into_iter().peekable() }
+    }
+
+    /// Elements in exactly one of `self` and `other` in sorted order.
+    ///
+    /// `other` must be sorted in strictly increasing order.
+    pub fn symmetric_difference<'a, I>(&'a self, other: I) -> SVSetSymmetricDifference<SVSetIter<'a, T>, I::IntoIter> where
+        I: IntoIterator<Item = &'a T>
+    {
+        SVSetSymmetricDifference { a: self.iter().peekable(), b: other.into_iter().peekable() }
+    }
+}
+
+impl<T: Ord, S: Strategy> core::iter::FromIterator<T> for SVSet<T, S> {
+    /// Keeps the first one of the equal elements.
+    fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
+        let mut sv = iter.into_iter().collect::<SortedVec<_>>();
+        sv.vec.dedup();
+
+        SVSet { map: sv.vec.into_iter().map(|x| (x, ())).collect() }
+    }
+}
+
+impl<T: Ord, S> IntoIterator for SVSet<T, S> {
+    type Item = T;
+    type IntoIter = SVSetIntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        SVSetIntoIter { iter: self.map.into_iter() }
+    }
+}
+
+impl<'a, T: Ord, S: Strategy> IntoIterator for &'a SVSet<T, S> {
+    type Item = &'a T;
+    type IntoIter = SVSetIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+
+/// Iterator over the elements of an [`SVSet`] in sorted order.
+pub struct SVSetIter<'a, T> {
+    keys: SVMapKeys<'a, T, ()>,
+}
+
+impl<'a, T: Ord> Iterator for SVSetIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.keys.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl<'a, T: Ord> DoubleEndedIterator for SVSetIter<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.keys.next_back()
+    }
+}
+
+impl<'a, T: Ord> ExactSizeIterator for SVSetIter<'a, T> {}
+
+impl<'a, T: Ord> FusedIterator for SVSetIter<'a, T> {}
+
+
+/// Owning iterator over the elements of an [`SVSet`] in sorted order.
+pub struct SVSetIntoIter<T> {
+    iter: SVMapKV<T, ()>,
+}
+
+impl<T: Ord> Iterator for SVSetIntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(x, _)| x)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T: Ord> DoubleEndedIterator for SVSetIntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back().map(|(x, _)| x)
+    }
+}
+
+impl<T: Ord> ExactSizeIterator for SVSetIntoIter<T> {}
+
+impl<T: Ord> FusedIterator for SVSetIntoIter<T> {}
+
+
+/// Iterator over a range of an [`SVSet`] in sorted order.
+pub struct SVSetRange<'a, T> {
+    range: SVMapRange<'a, T, ()>,
+}
+
+impl<'a, T: Ord> Iterator for SVSetRange<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.range.next().map(|(x, _)| x)
+    }
+}
+
+impl<'a, T: Ord> DoubleEndedIterator for SVSetRange<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.range.next_back().map(|(x, _)| x)
+    }
+}
+
+impl<'a, T: Ord> FusedIterator for SVSetRange<'a, T> {}
+
+
+/// Lazy union of two sorted sequences. See [`SVSet::union`].
+pub struct SVSetUnion<A: Iterator, B: Iterator> {
+    a: Peekable<A>,
+    b: Peekable<B>,
+}
+
+impl<'a, T: Ord + 'a, A, B> Iterator for SVSetUnion<A, B> where
+    A: Iterator<Item = &'a T>,
+    B: Iterator<Item = &'a T>,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match (self.a.peek(), self.b.peek()) {
+            (Some(x), Some(y)) => match x.cmp(y) {
+                Ordering::Less => self.a.next(),
+                Ordering::Greater => self.b.next(),
+
+                Ordering::Equal => {
+                    self.b.next();
+                    self.a.next()
+                }
+            }
+
+            (Some(_), None) => self.a.next(),
+            (None, _) => self.b.next(),
+        }
+    }
+}
+
+
+/// Lazy intersection of two sorted sequences. See [`SVSet::intersection`].
+pub struct SVSetIntersection<A: Iterator, B: Iterator> {
+    a: Peekable<A>,
+    b: Peekable<B>,
+}
+
+impl<'a, T: Ord + 'a, A, B> Iterator for SVSetIntersection<A, B> where
+    A: Iterator<Item = &'a T>,
+    B: Iterator<Item = &'a T>,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.a.peek()?.cmp(self.b.peek()?) {
+                Ordering::Less => { self.a.next(); }
+                Ordering::Greater => { self.b.next(); }
+
+                Ordering::Equal => {
+                    self.b.next();
+                    return self.a.next();
+                }
+            }
+        }
+    }
+}
+
+
+/// Lazy difference of two sorted sequences. See [`SVSet::difference`].
+pub struct SVSetDifference<A: Iterator, B: Iterator> {
+    a: Peekable<A>,
+    b: Peekable<B>,
+}
+
+impl<'a, T: Ord + 'a, A, B> Iterator for SVSetDifference<A, B> where
+    A: Iterator<Item = &'a T>,
+    B: Iterator<Item = &'a T>,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let x = self.a.peek()?;
+
+            match self.b.peek().map(|y| x.cmp(y)) {
+                None | Some(Ordering::Less) => { return self.a.next(); }
+                Some(Ordering::Greater) => { self.b.next(); }
+
+                Some(Ordering::Equal) => {
+                    self.a.next();
+                    self.b.next();
+                }
+            }
+        }
+    }
+}
+
+
+/// Lazy symmetric difference of two sorted sequences.
+/// See [`SVSet::symmetric_difference`].
+pub struct SVSetSymmetricDifference<A: Iterator, B: Iterator> {
+    a: Peekable<A>,
+    b: Peekable<B>,
+}
+
+impl<'a, T: Ord + 'a, A, B> Iterator for SVSetSymmetricDifference<A, B> where
+    A: Iterator<Item = &'a T>,
+    B: Iterator<Item = &'a T>,
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match (self.a.peek(), self.b.peek()) {
+                (Some(x), Some(y)) => match x.cmp(y) {
+                    Ordering::Less => { return self.a.next(); }
+                    Ordering::Greater => { return self.b.next(); }
+
+                    Ordering::Equal => {
+                        self.a.next();
+                        self.b.next();
+                    }
+                }
+
+                (Some(_), None) => { return self.a.next(); }
+                (None, _) => { return self.b.next(); }
+            }
+        }
+    }
+}
@@ -0,0 +1,560 @@
+//! A map with front-coded string keys.
+
+use crate::*;
+use crate::buffered::Buffered;
+use super::iter::{ Merge, MergeItem };
+use super::RebuildPolicy;
+use alloc::string::String;
+
+
+/// The number of keys between two keys stored in full.
+const BLOCK: usize = 16;
+
+
+/// An associative array with string keys stored compactly.
+///
+/// Each unit stores its sorted keys with front coding: a key keeps only
+/// the suffix after the prefix shared with the previous key, and every
+/// 16th key is stored in full. All the suffixes of a unit share a single
+/// allocation, so keys with long common prefixes (paths, URLs,
+/// identifiers) take much less memory than in
+/// [`SVMap<String, V>`](super::SVMap).
+///
+/// Lookups search the full keys and then decode up to 16 keys. Iterators
+/// decode the keys, so they yield owned `String`s.
+///
+/// ```
+/// # #[cfg(feature="sorted_vec")] {
+/// use dynamization::sorted_vec::SVStrMap;
+///
+/// let mut map = SVStrMap::new();
+///
+/// map.insert("/usr/bin/cargo", 1);
+/// map.insert("/usr/bin/rustc", 2);
+/// map.insert("/usr/lib/libc.so", 3);
+///
+/// assert_eq!(map.get("/usr/bin/rustc"), Some(&2));
+///
+/// let bin: Vec<String> = map.prefix("/usr/bin/").map(|(key, _)| key).collect();
+/// assert_eq!(bin, ["/usr/bin/cargo", "/usr/bin/rustc"]);
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct SVStrMap<V, S = strategy::Binary> {
+    dynamic: Buffered<FrontCoded<V>, S>,
+    len: usize,
+    policy: RebuildPolicy,
+}
+
+/// Sorted unique keys with front coding and their values: `None` for
+/// a removed entry.
+#[derive(Clone, Debug)]
+struct FrontCoded<V> {
+    /// The key suffixes one after another.
+    text: String,
+
+    /// For each key: the length of the prefix shared with the previous key
+    /// (zero for each `BLOCK`-th key) and the end of its suffix in `text`.
+    layout: Vec<(usize, usize)>,
+
+    values: Vec<Option<V>>,
+
+    /// The number of removed entries.
+    free_count: usize,
+}
+
+/// The length of the common prefix of two strings in bytes.
+fn common_prefix(a: &str, b: &str) -> usize {
+    let mut len = a.bytes().zip(b.bytes()).take_while(|(x, y)| x == y).count();
+
+    while !b.is_char_boundary(len) {
+        len -= 1;
+    }
+
+    len
+}
+
+impl<V> FrontCoded<V> {
+    fn new() -> Self {
+        FrontCoded { text: String::new(), layout: Vec::new(), values: Vec::new(), free_count: 0 }
+    }
+
+    /// Appends a key greater than all the keys in the unit.
+    ///
+    /// `last` is the last key of the unit: it's replaced by `key`.
+    fn push(&mut self, last: &mut String, key: &str, value: Option<V>) {
+        let shared = if self.layout.len() % BLOCK == 0 {
+            0
+        } else {
+            common_prefix(last, key)
+        };
+
+        if value.is_none() {
+            self.free_count += 1;
+        }
+
+        self.text.push_str(&key[shared..]);
+        self.layout.push((shared, self.text.len()));
+        self.values.push(value);
+
+        last.truncate(shared);
+        last.push_str(&key[shared..]);
+    }
+
+    fn suffix(&self, index: usize) -> &str {
+        let start = match index {
+            0 => 0,
+            _ => self.layout[index - 1].1,
+        };
+
+        &self.text[start..self.layout[index].1]
+    }
+
+    /// Decodes the key from the beginning of its block.
+    fn key(&self, index: usize) -> String {
+        let mut key = String::new();
+
+        for i in index - index % BLOCK..=index {
+            key.truncate(self.layout[i].0);
+            key.push_str(self.suffix(i));
+        }
+
+        key
+    }
+
+    /// The first index where `pred` is `false` (`pred` must be monotonic).
+    fn partition_point<P: Fn(&str) -> bool>(&self, pred: P) -> usize {
+        let len = self.layout.len();
+
+        // The full keys are searched first.
+        let mut low = 0;
+        let mut high = (len + BLOCK - 1) / BLOCK;
+
+        while low < high {
+            let middle = (low + high) / 2;
+
+            if pred(self.suffix(middle * BLOCK)) {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+
+        if low == 0 {
+            return 0;
+        }
+
+        let block = low - 1;
+        let end = core::cmp::min(low * BLOCK, len);
+        let mut key = String::new();
+
+        for i in block * BLOCK..end {
+            key.truncate(self.layout[i].0);
+            key.push_str(self.suffix(i));
+
+            if !pred(&key) {
+                return i;
+            }
+        }
+
+        end
+    }
+
+    fn find(&self, key: &str) -> Option<usize> {
+        let index = self.partition_point(|other| other < key);
+
+        if index < self.layout.len() && self.key(index) == key {
+            Some(index)
+        } else {
+            None
+        }
+    }
+
+    /// Drops the removed entries.
+    fn compact(&mut self) {
+        *self = core::mem::replace(self, FrontCoded::new()).merge_with(FrontCoded::new());
+    }
+
+    /// Takes the entries out in sorted order.
+    fn into_entries(self) -> impl Iterator<Item = (String, Option<V>)> {
+        let FrontCoded { text, layout, values, .. } = self;
+        let mut key = String::new();
+        let mut start = 0;
+
+        layout.into_iter().zip(values).map(move |((shared, end), value)| {
+            key.truncate(shared);
+            key.push_str(&text[start..end]);
+            start = end;
+
+            (key.clone(), value)
+        })
+    }
+}
+
+impl<V> Static for FrontCoded<V> {
+    fn len(&self) -> usize {
+        self.layout.len()
+    }
+
+    /// Drops the removed entries: the keys are unique, so they shadow
+    /// nothing.
+    fn merge_with(self, other: Self) -> Self {
+        let mut result = FrontCoded::new();
+        let mut last = String::new();
+
+        let mut a = self.into_entries().peekable();
+        let mut b = other.into_entries().peekable();
+
+        loop {
+            let entry = match (a.peek(), b.peek()) {
+                (None, None) => { break; }
+                (Some(_), None) => a.next(),
+                (None, Some(_)) => b.next(),
+                (Some(x), Some(y)) => if x.0 < y.0 { a.next() } else { b.next() },
+            };
+
+            if let Some((key, value @ Some(_))) = entry {
+                result.push(&mut last, &key, value);
+            }
+        }
+
+        result
+    }
+}
+
+impl<V> Singleton for FrontCoded<V> {
+    type Item = (String, V);
+
+    fn singleton((key, value): Self::Item) -> Self {
+        let mut unit = FrontCoded::new();
+        unit.push(&mut String::new(), &key, Some(value));
+
+        unit
+    }
+}
+
+impl<V> Insert for FrontCoded<V> {
+    /// Appends a key greater than the last one. Otherwise re-encodes
+    /// the keys from the block of the key onward: the blocks after it
+    /// start one key later.
+    fn insert(&mut self, (key, value): Self::Item) {
+        let len = self.layout.len();
+        let index = self.partition_point(|other| other < key.as_str());
+
+        if index < len && self.key(index) == key {
+            if self.values[index].replace(value).is_none() {
+                self.free_count -= 1;
+            }
+
+            return;
+        }
+
+        if index == len {
+            let mut last = match len {
+                0 => String::new(),
+                _ => self.key(len - 1),
+            };
+
+            self.push(&mut last, &key, Some(value));
+            return;
+        }
+
+        let start = index - index % BLOCK;
+        let text_start = match start {
+            0 => 0,
+            _ => self.layout[start - 1].1,
+        };
+
+        let mut decoded = String::new();
+        let keys: Vec<String> = (start..len).map(|i| {
+            decoded.truncate(self.layout[i].0);
+            decoded.push_str(self.suffix(i));
+            decoded.clone()
+        }).collect();
+
+        let mut rest: Vec<_> = keys.into_iter().zip(self.values.drain(start..)).collect();
+        rest.insert(index - start, (key, Some(value)));
+
+        self.text.truncate(text_start);
+        self.layout.truncate(start);
+        self.free_count -= rest.iter().filter(|entry| entry.1.is_none()).count();
+
+        let mut last = String::new();
+
+        for (key, value) in rest {
+            self.push(&mut last, &key, value);
+        }
+    }
+}
+
+
+/// An entry as seen by a [`Merge`].
+struct StrItem<'a, V> {
+    key: String,
+    value: &'a Option<V>,
+}
+
+impl<'a, V> MergeItem for StrItem<'a, V> {
+    type Key = String;
+    type Output = (String, &'a V);
+
+    fn key(&self) -> &String {
+        &self.key
+    }
+
+    fn into_output(self) -> Option<Self::Output> {
+        let key = self.key;
+        self.value.as_ref().map(|value| (key, value))
+    }
+}
+
+/// Decodes the keys of `unit.layout[front..back]`.
+struct UnitIter<'a, V> {
+    unit: &'a FrontCoded<V>,
+    front: usize,
+    back: usize,
+
+    /// The key at `front - 1` if decoded.
+    previous: Option<String>,
+}
+
+impl<'a, V> Iterator for UnitIter<'a, V> {
+    type Item = StrItem<'a, V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+
+        let index = self.front;
+        self.front += 1;
+
+        let key = match self.previous.take() {
+            Some(mut key) => {
+                key.truncate(self.unit.layout[index].0);
+                key.push_str(self.unit.suffix(index));
+                key
+            }
+
+            None => self.unit.key(index),
+        };
+
+        self.previous = Some(key.clone());
+
+        Some(StrItem { key, value: &self.unit.values[index] })
+    }
+}
+
+impl<'a, V> DoubleEndedIterator for UnitIter<'a, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+
+        self.back -= 1;
+
+        Some(StrItem { key: self.unit.key(self.back), value: &self.unit.values[self.back] })
+    }
+}
+
+
+/// Iterator over `(key, &value)` pairs of an [`SVStrMap`] sorted by key.
+pub struct SVStrMapIter<'a, V> {
+    merge: Merge<UnitIter<'a, V>>,
+}
+
+impl<'a, V> Iterator for SVStrMapIter<'a, V> {
+    type Item = (String, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.merge.next_front()
+    }
+}
+
+impl<'a, V> DoubleEndedIterator for SVStrMapIter<'a, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.merge.next_back()
+    }
+}
+
+impl<'a, V> core::iter::FusedIterator for SVStrMapIter<'a, V> {}
+
+
+impl<V> SVStrMap<V> {
+    /// Uses the [`Binary`](strategy::Binary) strategy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses a specified strategy.
+    pub fn with_strategy<S: Strategy>() -> SVStrMap<V, S> {
+        SVStrMap::default()
+    }
+}
+
+impl<V, S: Strategy> Default for SVStrMap<V, S> {
+    fn default() -> Self {
+        SVStrMap {
+            dynamic: Buffered::new(),
+            len: 0,
+            policy: RebuildPolicy::default(),
+        }
+    }
+}
+
+
+impl<V, S: Strategy> SVStrMap<V, S> {
+    /// Returns the number of elements currently stored.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `self.len() == 0`.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Finds the entry with the key (possibly a removed one).
+    ///
+    /// Returns the number of its unit in `units()` and its index in the unit.
+    fn locate(&self, key: &str) -> Option<(usize, usize)> {
+        self.dynamic.units()
+            .enumerate()
+            .find_map(|(unit_no, unit)| unit.find(key).map(|index| (unit_no, index)))
+    }
+
+    fn unit_mut(&mut self, unit_no: usize) -> Option<&mut FrontCoded<V>> {
+        self.dynamic.units_mut().nth(unit_no)
+    }
+
+    /// Returns `true` if the key is present.
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns a shared reference to the value or `None`.
+    pub fn get(&self, key: &str) -> Option<&V> {
+        let (unit_no, index) = self.locate(key)?;
+
+        self.dynamic.units().nth(unit_no)?.values[index].as_ref()
+    }
+
+    /// Returns an exclusive reference to the value or `None`.
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut V> {
+        let (unit_no, index) = self.locate(key)?;
+
+        self.unit_mut(unit_no)?.values[index].as_mut()
+    }
+
+    /// Inserts a new key-value pair into the map.
+    ///
+    /// Returns the old value if it has been present.
+    pub fn insert(&mut self, key: &str, value: V) -> Option<V> {
+        if let Some((unit_no, index)) = self.locate(key) {
+            let unit = self.unit_mut(unit_no)?;
+            let result = unit.values[index].replace(value);
+
+            if result.is_none() {
+                unit.free_count -= 1;
+                self.len += 1;
+            }
+
+            return result;
+        }
+
+        self.dynamic.insert((String::from(key), value));
+        self.len += 1;
+
+        None
+    }
+
+    /// Removes an item from the container.
+    ///
+    /// Returns the item removed or `None` if the item has not been found.
+    pub fn remove(&mut self, key: &str) -> Option<V> {
+        let (unit_no, index) = self.locate(key)?;
+        let unit = self.unit_mut(unit_no)?;
+        let result = unit.values[index].take();
+
+        if result.is_some() {
+            unit.free_count += 1;
+            self.len -= 1;
+        }
+
+        self.rebuild_if_sparse();
+
+        result
+    }
+
+    /// The policy for dropping the removed entries.
+    pub fn rebuild_policy(&self) -> RebuildPolicy {
+        self.policy
+    }
+
+    /// Sets the policy for dropping the removed entries and applies it
+    /// (see [`SVMap::set_rebuild_policy`](super::SVMap::set_rebuild_policy)).
+    pub fn set_rebuild_policy(&mut self, policy: RebuildPolicy) {
+        self.policy = policy;
+        self.rebuild_if_sparse();
+    }
+
+    /// Drops the removed entries if the policy says so.
+    fn rebuild_if_sparse(&mut self) {
+        let policy = self.policy;
+
+        if let RebuildPolicy::PerUnit(_) = policy {
+            for unit in self.dynamic.units_mut() {
+                if policy.compacts(unit.free_count, unit.len()) {
+                    unit.compact();
+                }
+            }
+        }
+
+        if policy.rebuilds(self.len, self.dynamic.len()) {
+            let dynamic = core::mem::take(&mut self.dynamic);
+
+            if let Some(unit) = dynamic.try_collect() {
+                // A single unit might have not been merged at all.
+                let unit = unit.merge_with(FrontCoded::new());
+
+                if !unit.is_empty() {
+                    self.dynamic.add_unit(unit);
+                }
+            }
+        }
+    }
+
+    /// Removes all elements from the map.
+    pub fn clear(&mut self) {
+        self.dynamic.clear();
+        self.len = 0;
+    }
+
+    /// Iterator over `(key, &value)` pairs sorted by key.
+    pub fn iter(&self) -> SVStrMapIter<'_, V> {
+        self.range_by(|unit| 0..unit.layout.len())
+    }
+
+    /// Iterator over `(key, &value)` pairs with the keys starting with
+    /// `prefix` sorted by key.
+    pub fn prefix(&self, prefix: &str) -> SVStrMapIter<'_, V> {
+        self.range_by(|unit| {
+            let start = unit.partition_point(|key| key < prefix);
+            let end = unit.partition_point(|key| key < prefix || key.starts_with(prefix));
+
+            start..end
+        })
+    }
+
+    fn range_by<B>(&self, bounds: B) -> SVStrMapIter<'_, V> where
+        B: Fn(&FrontCoded<V>) -> core::ops::Range<usize>
+    {
+        SVStrMapIter {
+            merge: Merge::new(self.dynamic.units().map(|unit| {
+                let bounds = bounds(unit);
+
+                UnitIter { unit, front: bounds.start, back: bounds.end, previous: None }
+            })),
+        }
+    }
+}
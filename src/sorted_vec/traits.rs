@@ -0,0 +1,100 @@
+//! Standard traits for [`SVMap`] over its logical contents: the sorted
+//! key-value pairs regardless of the units and the removed entries.
+
+use crate::*;
+use super::{ SVMap, Filter, sorted_pairs };
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::hash::{ Hash, Hasher };
+
+
+impl<K: Ord, V, S: Strategy, F: Filter<K>> Extend<(K, V)> for SVMap<K, V, S, F> {
+    /// Sorts the pairs into a single unit and adds it as a whole: 
+    /// each unit is scanned once against the sorted keys, and the values 
+    /// for the keys found there replace the stored ones (the later pairs 
+    /// win as in [`insert`](SVMap::insert)).
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        self.extend_sorted(sorted_pairs(iter));
+    }
+}
+
+impl<'a, K, V, S, F> Extend<(&'a K, &'a V)> for SVMap<K, V, S, F> where
+    K: Ord + Copy,
+    V: Copy,
+    S: Strategy,
+    F: Filter<K>,
+{
+    fn extend<I: IntoIterator<Item = (&'a K, &'a V)>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().map(|(&key, &value)| (key, value)));
+    }
+}
+
+impl<K: Ord, V, S: Strategy, F: Filter<K>, const N: usize> From<[(K, V); N]> for SVMap<K, V, S, F> {
+    /// The later pairs overwrite the earlier ones with the same key.
+    fn from(pairs: [(K, V); N]) -> Self {
+        core::iter::FromIterator::from_iter(pairs)
+    }
+}
+
+
+impl<K, Q, V, S, F> core::ops::Index<&Q> for SVMap<K, V, S, F> where
+    K: Ord + Borrow<Q>,
+    Q: Ord + ?Sized,
+    S: Strategy,
+    F: Filter<K> + Filter<Q>,
+{
+    type Output = V;
+
+    /// Returns a reference to the value for the key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is not present in the map.
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("no entry found for key")
+    }
+}
+
+
+impl<K, V, S, F> core::fmt::Debug for SVMap<K, V, S, F> where
+    K: Ord + core::fmt::Debug,
+    V: core::fmt::Debug,
+    S: Strategy,
+    F: Filter<K>,
+{
+    /// Formats the pairs as [`BTreeMap`](alloc::collections::BTreeMap) does.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+
+impl<K: Ord, V: PartialEq, S: Strategy, F: Filter<K>> PartialEq for SVMap<K, V, S, F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<K: Ord, V: Eq, S: Strategy, F: Filter<K>> Eq for SVMap<K, V, S, F> {}
+
+impl<K: Ord, V: PartialOrd, S: Strategy, F: Filter<K>> PartialOrd for SVMap<K, V, S, F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<K: Ord, V: Ord, S: Strategy, F: Filter<K>> Ord for SVMap<K, V, S, F> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<K: Ord + Hash, V: Hash, S: Strategy, F: Filter<K>> Hash for SVMap<K, V, S, F> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+
+        for pair in self.iter() {
+            pair.hash(state);
+        }
+    }
+}
@@ -1,6 +1,7 @@
 //! Different dynamization strategies.
 //!
-//! Currently only [`Binary`] is supported.
+//! [`Binary`] is the default one. [`SimpleBinary`], [`SkewBinary`] and
+//! [`Chunked`] differ in which units get merged.
 
 use crate::*;
 
@@ -68,7 +69,7 @@ impl Strategy for Binary {
         }
 
         for unit in &mut units[index..] {
-            let content = core::mem::replace(unit, None);
+            let content = unit.take();
             
             match content {
                 None => {
@@ -119,7 +120,7 @@ impl Strategy for SimpleBinary {
         mut container: Container)
     {
         for unit in &mut *units {
-            let content = core::mem::replace(unit, None);
+            let content = unit.take();
             
             match content {
                 None => {
@@ -169,13 +170,13 @@ impl Strategy for SkewBinary {
         units: &mut Vec<Option<Container>>, 
         mut container: Container)
     {
-        if units.len() == 0 {
+        if units.is_empty() {
             units.push(Some(container));
             return;
         }
 
         let unit = &mut units[self.last_merge];
-        let content = core::mem::replace(unit, None);
+        let content = unit.take();
             
         match content {
             None => {
@@ -197,7 +198,7 @@ impl Strategy for SkewBinary {
         }
 
         let unit = &mut units[new_index];
-        let content = core::mem::replace(unit, None);
+        let content = unit.take();
             
         match content {
             None => {
@@ -213,3 +214,68 @@ impl Strategy for SkewBinary {
     }
 }
 
+
+
+/// Chunked binary dynamization.
+///
+/// Works as [`SimpleBinary`] on the first `levels` units, but a unit carried 
+/// out of the last level becomes a frozen _chunk_ which never participates 
+/// in merges again.
+///
+/// All the units inside the binary part are newer than any chunk, so 
+/// old and new data are never merged together. This makes the strategy 
+/// a good fit for [`Windowed`](crate::windowed::Windowed) containers: 
+/// old chunks can be expired as a whole.
+///
+/// The unit count passed to [`with_unit_count`](Strategy::with_unit_count) 
+/// is used as the count of levels, so each chunk is made of at most 
+/// `2^levels` single-element insertions.
+#[derive(Clone, Debug)]
+pub struct Chunked {
+    levels: usize
+}
+
+impl Strategy for Chunked {
+    fn new_unit_count() -> (Self, usize) {
+        (Chunked { levels: 10 }, 16)
+    }
+
+    fn with_unit_count(unit_count: usize) -> Self {
+        assert!(unit_count > 0);
+
+        Chunked {
+            levels: unit_count,
+        }
+    }
+
+    fn add<Container: Static>(
+        &mut self, 
+        units: &mut Vec<Option<Container>>, 
+        mut container: Container)
+    {
+        while units.len() < self.levels {
+            units.push(None);
+        }
+
+        for unit in &mut units[..self.levels] {
+            let content = unit.take();
+            
+            match content {
+                None => {
+                    *unit = Some(container);
+                    return;
+                }
+
+                Some(other) => {
+                    container = container.merge_with(other);
+                }
+            }
+        }
+
+        // The carry becomes a chunk: reuse a slot of an expired one if any.
+        match units[self.levels..].iter_mut().find(|unit| unit.is_none()) {
+            Some(unit) => { *unit = Some(container); }
+            None => { units.push(Some(container)); }
+        }
+    }
+}
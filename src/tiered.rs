@@ -0,0 +1,183 @@
+//! Units with different representations depending on their size.
+//!
+//! Small units often benefit from a simpler representation than the big ones
+//! (e.g. a linear-scan array vs. a search tree). [`Tiered`] is a unit
+//! which is either small or large: a small unit is [upgraded](Upgrade)
+//! as soon as a merge or an [insertion](Insert) makes it larger than
+//! [`Upgrade::THRESHOLD`].
+//!
+//! ```
+//! use dynamization::{ Static, Singleton, Dynamic };
+//! use dynamization::tiered::{ Tiered, Upgrade };
+//!
+//! // Unsorted: linear scan
+//! struct Small(Vec<i32>);
+//!
+//! // Sorted: binary search
+//! struct Large(Vec<i32>);
+//!
+//! impl Static for Small {
+//!     fn len(&self) -> usize { self.0.len() }
+//!
+//!     fn merge_with(mut self, other: Self) -> Self {
+//!         self.0.extend(other.0);
+//!         self
+//!     }
+//! }
+//!
+//! impl Singleton for Small {
+//!     type Item = i32;
+//!
+//!     fn singleton(item: i32) -> Self { Small(vec![item]) }
+//! }
+//!
+//! impl Static for Large {
+//!     fn len(&self) -> usize { self.0.len() }
+//!
+//!     fn merge_with(mut self, other: Self) -> Self {
+//!         self.0.extend(other.0);
+//!         self.0.sort();
+//!         self
+//!     }
+//! }
+//!
+//! impl Upgrade<Large> for Small {
+//!     const THRESHOLD: usize = 8;
+//!
+//!     fn upgrade(mut self) -> Large {
+//!         self.0.sort();
+//!         Large(self.0)
+//!     }
+//! }
+//!
+//! let mut dynamic = Dynamic::<Tiered<Small, Large>>::new();
+//!
+//! for x in 0..100 {
+//!     dynamic.insert(x);
+//! }
+//!
+//! let contains = |x: i32| dynamic.units().any(|unit| match unit {
+//!     Tiered::Small(small) => small.0.contains(&x),
+//!     Tiered::Large(large) => large.0.binary_search(&x).is_ok(),
+//! });
+//!
+//! assert!(contains(42));
+//! assert!(!contains(100));
+//! ```
+
+use crate::*;
+
+
+/// A conversion from a small unit representation to a large one.
+pub trait Upgrade<Large>: Static {
+    /// The maximal size of a small unit.
+    const THRESHOLD: usize;
+
+    /// Converts a unit to the large representation.
+    fn upgrade(self) -> Large;
+}
+
+
+/// A unit which is either small or large.
+#[derive(Clone, Debug)]
+pub enum Tiered<Small, Large> {
+    /// A unit in the small representation.
+    Small(Small),
+
+    /// A unit in the large representation.
+    Large(Large),
+}
+
+impl<Small: Upgrade<Large>, Large> Tiered<Small, Large> {
+    /// Wraps a small container upgrading it if its size
+    /// exceeds the [threshold](Upgrade::THRESHOLD).
+    pub fn new(small: Small) -> Self {
+        if small.len() > Small::THRESHOLD {
+            Tiered::Large(small.upgrade())
+        } else {
+            Tiered::Small(small)
+        }
+    }
+}
+
+impl<Small, Large> Tiered<Small, Large> {
+    /// Returns `true` for a unit in the small representation.
+    pub fn is_small(&self) -> bool {
+        matches!(self, Tiered::Small(_))
+    }
+
+    /// Returns the small representation if any.
+    pub fn as_small(&self) -> Option<&Small> {
+        match self {
+            Tiered::Small(small) => Some(small),
+            Tiered::Large(_) => None,
+        }
+    }
+
+    /// Returns the large representation if any.
+    pub fn as_large(&self) -> Option<&Large> {
+        match self {
+            Tiered::Small(_) => None,
+            Tiered::Large(large) => Some(large),
+        }
+    }
+}
+
+impl<Small: Upgrade<Large>, Large: Static> Static for Tiered<Small, Large> {
+    fn len(&self) -> usize {
+        match self {
+            Tiered::Small(small) => small.len(),
+            Tiered::Large(large) => large.len(),
+        }
+    }
+
+    fn merge_with(self, other: Self) -> Self {
+        match (self, other) {
+            (Tiered::Small(a), Tiered::Small(b)) => {
+                Tiered::new(a.merge_with(b))
+            }
+
+            (Tiered::Small(a), Tiered::Large(b)) => {
+                Tiered::Large(a.upgrade().merge_with(b))
+            }
+
+            (Tiered::Large(a), Tiered::Small(b)) => {
+                Tiered::Large(a.merge_with(b.upgrade()))
+            }
+
+            (Tiered::Large(a), Tiered::Large(b)) => {
+                Tiered::Large(a.merge_with(b))
+            }
+        }
+    }
+}
+
+impl<Small: Singleton, Large> Singleton for Tiered<Small, Large> {
+    type Item = Small::Item;
+
+    fn singleton(item: Self::Item) -> Self {
+        Tiered::Small(Small::singleton(item))
+    }
+}
+
+impl<Small, Large> Insert for Tiered<Small, Large> where
+    Small: Insert + Upgrade<Large> + Default,
+    Large: Insert<Item = Small::Item>,
+{
+    /// Inserts into the current representation upgrading a small unit
+    /// which outgrows the [threshold](Upgrade::THRESHOLD)
+    /// (an empty `Small` is left in place for the duration of the upgrade).
+    fn insert(&mut self, item: Self::Item) {
+        match self {
+            Tiered::Small(small) => {
+                small.insert(item);
+
+                if small.len() > Small::THRESHOLD {
+                    *self = Tiered::new(core::mem::take(small));
+                }
+            }
+
+            Tiered::Large(large) => large.insert(item),
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! Time-windowed dynamization.
+//!
+//! Each unit of a [`Windowed`] container remembers the range of insertion
+//! timestamps of its content. So the data older than some moment can be
+//! expired unit-by-unit without looking inside the units.
+//!
+//! ```
+//! use dynamization::Static;
+//! use dynamization::windowed::Windowed;
+//!
+//! struct Events(Vec<(u32, &'static str)>);
+//!
+//! impl Static for Events {
+//!     fn len(&self) -> usize { self.0.len() }
+//!
+//!     fn merge_with(mut self, other: Self) -> Self {
+//!         self.0.extend(other.0);
+//!         self
+//!     }
+//! }
+//!
+//! let mut events = Windowed::<Events, u32>::new();
+//!
+//! events.add_unit(Events(vec![(1, "a"), (2, "b")]), 1, 2);
+//! events.add_unit(Events(vec![(3, "c"), (5, "d")]), 3, 5);
+//! assert_eq!(events.len(), 4);
+//!
+//! // The two units have been merged into one straddling the moment `3`:
+//! events.expire_before(&3);
+//! assert_eq!(events.len(), 4);
+//!
+//! events.expire_before_with(&3, |unit, time| {
+//!     unit.0.retain(|event| event.0 >= *time);
+//! });
+//! assert_eq!(events.len(), 2);
+//!
+//! // Now the whole unit is out of the window:
+//! events.expire_before(&6);
+//! assert!(events.is_empty());
+//! ```
+
+use crate::*;
+
+
+/// A unit tagged with the range of insertion timestamps of its content.
+#[derive(Clone, Debug)]
+pub struct Timed<Container, T> {
+    container: Container,
+    min_time: T,
+    max_time: T,
+}
+
+impl<Container, T> Timed<Container, T> {
+    /// The partial container itself. Shared-reference version.
+    pub fn container(&self) -> &Container {
+        &self.container
+    }
+
+    /// The partial container itself. Unique-reference version.
+    ///
+    /// Removing items is fine but items inserted through this reference
+    /// must not be older than [`min_time`](Timed::min_time)
+    /// or newer than [`max_time`](Timed::max_time).
+    pub fn container_mut(&mut self) -> &mut Container {
+        &mut self.container
+    }
+
+    /// A lower bound for the insertion timestamps in the unit.
+    pub fn min_time(&self) -> &T {
+        &self.min_time
+    }
+
+    /// An upper bound for the insertion timestamps in the unit.
+    pub fn max_time(&self) -> &T {
+        &self.max_time
+    }
+}
+
+impl<Container: Static, T: Ord> Static for Timed<Container, T> {
+    fn len(&self) -> usize {
+        self.container.len()
+    }
+
+    fn merge_with(self, other: Self) -> Self {
+        Timed {
+            container: self.container.merge_with(other.container),
+            min_time: core::cmp::min(self.min_time, other.min_time),
+            max_time: core::cmp::max(self.max_time, other.max_time),
+        }
+    }
+}
+
+
+/// A dynamic version of `Container` with a notion of unit age.
+///
+/// Uses the [`Chunked`](strategy::Chunked) strategy by default:
+/// other strategies are allowed but can merge old and new data together
+/// making expiration less precise.
+#[derive(Clone, Debug)]
+pub struct Windowed<Container, T, S = strategy::Chunked> {
+    dynamic: Dynamic<Timed<Container, T>, S>,
+}
+
+impl<Container: Static, T: Ord, S: Strategy> Windowed<Container, T, S> {
+    /// A new container with a default initial unit count.
+    pub fn new() -> Self {
+        Windowed {
+            dynamic: Dynamic::new(),
+        }
+    }
+
+    /// A new container with a specified initial unit count.
+    pub fn with_unit_count(unit_count: usize) -> Self {
+        Windowed {
+            dynamic: Dynamic::with_unit_count(unit_count),
+        }
+    }
+
+    /// Adds a new unit with the insertion timestamps
+    /// between `min_time` and `max_time`.
+    pub fn add_unit(&mut self, container: Container, min_time: T, max_time: T) {
+        assert!(min_time <= max_time);
+
+        self.dynamic.add_unit(Timed { container, min_time, max_time });
+    }
+
+    /// Total size of the container.
+    pub fn len(&self) -> usize {
+        self.dynamic.len()
+    }
+
+    /// Returns `true` if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.dynamic.is_empty()
+    }
+
+    /// Iterator over all the timed units. Shared-reference version.
+    pub fn units(&self) -> Units<'_, Timed<Container, T>> {
+        self.dynamic.units()
+    }
+
+    /// Iterator over all the timed units. Unique-reference version.
+    pub fn units_mut(&mut self) -> UnitsMut<'_, Timed<Container, T>> {
+        self.dynamic.units_mut()
+    }
+
+    /// Drops all the units containing only the items
+    /// inserted strictly before `time`.
+    ///
+    /// The units straddling `time` are kept intact.
+    pub fn expire_before(&mut self, time: &T) {
+        self.dynamic.retain_units(|unit| unit.max_time >= *time);
+    }
+
+    /// Clears all the partial containers.
+    pub fn clear(&mut self) {
+        self.dynamic.clear();
+    }
+}
+
+impl<Container: Static, T: Ord + Clone, S: Strategy> Windowed<Container, T, S> {
+    /// Like [`expire_before`](Windowed::expire_before) but also
+    /// calls `filter` on each unit straddling `time`.
+    ///
+    /// The `filter` must remove all the items inserted strictly before `time`.
+    /// Units becoming empty are dropped.
+    pub fn expire_before_with<F>(&mut self, time: &T, mut filter: F) where
+        F: FnMut(&mut Container, &T)
+    {
+        self.dynamic.retain_units(|unit| {
+            if unit.max_time < *time { return false; }
+
+            if unit.min_time < *time {
+                filter(&mut unit.container, time);
+                unit.min_time = time.clone();
+            }
+
+            !unit.container.is_empty()
+        });
+    }
+}
+
+impl<Container: Static + Singleton, T: Ord + Clone, S: Strategy> Windowed<Container, T, S> {
+    /// Inserts a single item with the timestamp `time`.
+    ///
+    /// Requires [`Singleton`] to be implemented for the container type.
+    pub fn insert(&mut self, time: T, item: Container::Item) {
+        self.add_unit(Container::singleton(item), time.clone(), time);
+    }
+}
+
+impl<Container: Static, T: Ord, S: Strategy> Default for Windowed<Container, T, S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
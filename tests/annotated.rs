@@ -0,0 +1,81 @@
+use dynamization::{ Static, Singleton, Dynamic };
+use dynamization::annotated::{ Annotated, Summary };
+use dynamization::strategy;
+
+#[derive(Clone, Debug)]
+struct Bag(Vec<u32>);
+
+impl Static for Bag {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn merge_with(mut self, other: Self) -> Self {
+        self.0.extend(other.0);
+        self
+    }
+}
+
+impl Singleton for Bag {
+    type Item = u32;
+
+    fn singleton(item: Self::Item) -> Self {
+        Bag(vec![item])
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Meta {
+    min: u32,
+    max: u32,
+    count: usize,
+    sum: u64,
+}
+
+impl Summary<Bag> for Meta {
+    fn summarize(bag: &Bag) -> Self {
+        Meta {
+            min: *bag.0.iter().min().unwrap(),
+            max: *bag.0.iter().max().unwrap(),
+            count: bag.0.len(),
+            sum: bag.0.iter().map(|&x| x as u64).sum(),
+        }
+    }
+
+    fn combine(self, other: Self) -> Self {
+        Meta {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+            count: self.count + other.count,
+            sum: self.sum + other.sum,
+        }
+    }
+}
+
+
+#[test]
+fn test_meta() {
+    test_meta_strategy::<strategy::Binary>();
+    test_meta_strategy::<strategy::SimpleBinary>();
+    test_meta_strategy::<strategy::SkewBinary>();
+}
+
+fn test_meta_strategy<S: strategy::Strategy>() {
+    use rand::{ Rng, SeedableRng };
+    
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    let mut dynamic = Dynamic::<Annotated<Bag, Meta>, S>::new();
+
+    for i in 0..500 {
+        if i % 50 == 0 {
+            let bag = Bag((0..10).map(|_| rng.gen_range(0, 1000)).collect());
+            dynamic.add_unit(Annotated::new(bag));
+        } else {
+            dynamic.insert(rng.gen_range(0, 1000));
+        }
+
+        for (bag, meta) in dynamic.units_with_meta() {
+            assert_eq!(meta, &Meta::summarize(bag));
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use dynamization::sorted_vec::{ SVCola, SVColaMap };
+use std::collections::BTreeMap;
+
+
+#[test]
+fn test_contains() {
+    use rand::{ Rng, SeedableRng };
+    
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+    for &size in &[0, 1, 2, 3, 4, 5, 10, 100, 1000, 5000] {
+        let mut cola = SVCola::<i32>::new();
+        let mut counts = BTreeMap::<i32, usize>::new();
+
+        for _ in 0..size {
+            let x = rng.gen_range(0, 2 * size + 1);
+
+            cola.insert(x);
+            *counts.entry(x).or_default() += 1;
+
+            assert_eq!(cola.len(), counts.values().sum());
+
+            for _ in 0..10 {
+                let y = rng.gen_range(-1, 2 * size + 2);
+                assert_eq!(cola.contains(&y), counts.contains_key(&y));
+            }
+        }
+
+        for x in -1..=2 * size + 1 {
+            assert_eq!(cola.get(&x), counts.get_key_value(&x).map(|kv| kv.0));
+        }
+    }
+}
+
+#[test]
+fn test_from_iter() {
+    let cola = (0..100).map(|x| x * 2).collect::<SVCola<_>>();
+
+    assert_eq!(cola.len(), 100);
+
+    for x in 0..200 {
+        assert_eq!(cola.contains(&x), x % 2 == 0);
+    }
+}
+
+#[test]
+fn test_map() {
+    use rand::{ Rng, SeedableRng };
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+    for &size in &[0, 1, 2, 3, 4, 5, 10, 100, 1000, 5000] {
+        let mut map = SVColaMap::<i32, i32>::new();
+        let mut reference = BTreeMap::<i32, i32>::new();
+
+        for i in 0..3 * size {
+            let x = rng.gen_range(0, 2 * size + 1);
+
+            match rng.gen_range(0, 3) {
+                0 => {
+                    assert_eq!(map.remove(&x), reference.remove(&x));
+                }
+
+                1 => {
+                    if let Some(value) = map.get_mut(&x) { *value += 1; }
+                    if let Some(value) = reference.get_mut(&x) { *value += 1; }
+                }
+
+                _ => {
+                    assert_eq!(map.insert(x, i), reference.insert(x, i));
+                }
+            }
+
+            assert_eq!(map.len(), reference.len());
+
+            for _ in 0..10 {
+                let y = rng.gen_range(-1, 2 * size + 2);
+                assert_eq!(map.get(&y), reference.get(&y));
+            }
+        }
+
+        for x in -1..=2 * size + 1 {
+            assert_eq!(map.contains_key(&x), reference.contains_key(&x));
+        }
+
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(map.get(&0), None);
+    }
+}
+
+#[test]
+fn test_map_from_iter() {
+    let mut map = (0..100).map(|x| (x % 50, x)).collect::<SVColaMap<_, _>>();
+
+    assert_eq!(map.len(), 50);
+
+    for x in 0..50 {
+        assert_eq!(map.get(&x), Some(&(x + 50)));
+        assert_eq!(map.remove(&x), Some(x + 50));
+    }
+
+    assert!(map.is_empty());
+    assert_eq!(map.get(&0), None);
+}
@@ -0,0 +1,87 @@
+use dynamization::sorted_vec::SVLsmMap;
+use dynamization::strategy;
+use std::collections::BTreeMap;
+
+
+#[test]
+fn test_assoc() {
+    test_assoc_strategy::<strategy::Binary>();
+    test_assoc_strategy::<strategy::SimpleBinary>();
+    test_assoc_strategy::<strategy::SkewBinary>();
+    test_assoc_strategy::<strategy::Chunked>();
+}
+
+fn test_assoc_strategy<S: strategy::Strategy>() {
+    use rand::{ Rng, SeedableRng };
+    
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+    for &size in &[0, 1, 2, 3, 4, 5, 10, 100, 1000, 10000] {
+        let mut lsm = SVLsmMap::<i32,i32,S>::default();
+        let mut btree = BTreeMap::<i32,i32>::new();
+
+        for _ in 0..size {
+            let k = rng.gen_range(0, 100);
+            let v = rng.gen();
+
+            if rng.gen_range(0, 10) < 6 {
+                lsm.insert(k, v);
+                btree.insert(k, v);
+            } else {
+                lsm.remove(k);
+                btree.remove(&k);
+            }
+
+            for ref k in 0..100 {
+                assert_eq!(lsm.get(k), btree.get(k));
+            }
+        }
+
+        lsm.compact();
+        assert_eq!(lsm.stored_len(), btree.len());
+
+        for ref k in 0..100 {
+            assert_eq!(lsm.get(k), btree.get(k));
+        }
+    }
+}
+
+
+#[test]
+fn test_tombstones() {
+    let mut lsm = SVLsmMap::<u32,u32>::new();
+
+    for x in 0..10000 {
+        lsm.insert(x, x);
+    }
+
+    for x in 0..10000 {
+        lsm.remove(x);
+    }
+
+    // The merges involving the oldest writes drop the tombstones.
+    assert!(lsm.stored_len() < 10000);
+
+    for x in 0..10000 {
+        assert!(!lsm.contains_key(&x));
+    }
+
+    lsm.compact();
+    assert_eq!(lsm.stored_len(), 0);
+}
+
+
+#[test]
+fn test_from_iter() {
+    let mut lsm: SVLsmMap<_, _> = vec![(3, 'a'), (1, 'b'), (3, 'c')].into_iter().collect();
+
+    assert_eq!(lsm.get(&3), Some(&'c'));
+    assert_eq!(lsm.get(&1), Some(&'b'));
+    assert_eq!(lsm.stored_len(), 2);
+
+    lsm.remove(3);
+    *lsm.get_mut(&1).unwrap() = 'd';
+
+    assert_eq!(lsm.get(&3), None);
+    assert_eq!(lsm.get(&1), Some(&'d'));
+}
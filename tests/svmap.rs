@@ -1,22 +1,24 @@
-use dynamization::sorted_vec::SVMap;
+use dynamization::sorted_vec::{ SVMap, Filter, NoFilter, Bloom, Bloom10, RebuildPolicy };
 use dynamization::strategy;
 use std::collections::BTreeMap;
 
 
 #[test]
 fn test_assoc() {
-    test_assoc_strategy::<strategy::Binary>();
-    test_assoc_strategy::<strategy::SimpleBinary>();
-    test_assoc_strategy::<strategy::SkewBinary>();
+    test_assoc_strategy::<strategy::Binary, NoFilter>();
+    test_assoc_strategy::<strategy::SimpleBinary, NoFilter>();
+    test_assoc_strategy::<strategy::SkewBinary, NoFilter>();
+    test_assoc_strategy::<strategy::Binary, Bloom10>();
+    test_assoc_strategy::<strategy::SkewBinary, Bloom<4>>();
 }
 
-fn test_assoc_strategy<S: strategy::Strategy>() {
+fn test_assoc_strategy<S: strategy::Strategy, F: Filter<i32>>() {
     use rand::{ Rng, SeedableRng };
     
     let mut rng = rand::rngs::StdRng::seed_from_u64(42);
 
     for &size in &[0, 1, 2, 3, 4, 5, 10, 100, 1000, 10000] {
-        let mut svmap = SVMap::<i32,i32>::new();
+        let mut svmap = SVMap::<i32,i32,S,F>::default();
         let mut btree = BTreeMap::<i32,i32>::new();
 
         for _ in 0..size {
@@ -39,3 +41,797 @@ fn test_assoc_strategy<S: strategy::Strategy>() {
     }
 }
 
+
+
+#[test]
+fn test_bloom() {
+    let keys: Vec<u64> = (0..10000).map(|x| x * 2).collect();
+    let bloom = <Bloom10 as Filter<u64>>::build(keys.iter());
+
+    for key in &keys {
+        assert!(bloom.may_contain(key));
+    }
+
+    let false_positives = (0..10000)
+        .filter(|x| bloom.may_contain(&(x * 2 + 1)))
+        .count();
+
+    assert!(false_positives < 300);
+}
+
+
+#[test]
+fn test_monotonic() {
+    let mut svmap = SVMap::<u32,u32>::new();
+    let mut btree = BTreeMap::<u32,u32>::new();
+
+    for id in 0..3000 {
+        assert_eq!(svmap.insert(id, id * 2), btree.insert(id, id * 2));
+
+        if id % 3 == 0 {
+            let old = id / 2;
+            assert_eq!(svmap.remove(&old), btree.remove(&old));
+        }
+
+        for k in &[0, id / 3, id / 2, id, id + 1] {
+            assert_eq!(svmap.get(k), btree.get(k));
+        }
+    }
+}
+
+
+#[test]
+fn test_iter() {
+    use rand::{ Rng, SeedableRng };
+    
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+    for &size in &[0, 1, 2, 3, 4, 5, 10, 100, 1000] {
+        let mut svmap = SVMap::<i32,i32>::new();
+        let mut btree = BTreeMap::<i32,i32>::new();
+
+        for _ in 0..size {
+            let k = rng.gen_range(0, 100);
+            let v = rng.gen();
+
+            if rng.gen_range(0, 10) < 7 {
+                svmap.insert(k, v);
+                btree.insert(k, v);
+            } else {
+                svmap.remove(&k);
+                btree.remove(&k);
+            }
+        }
+
+        assert!(svmap.iter().eq(btree.iter()));
+        assert!(svmap.iter().rev().eq(btree.iter().rev()));
+        assert!(svmap.keys().eq(btree.keys()));
+        assert!(svmap.values().rev().eq(btree.values().rev()));
+        assert_eq!(svmap.iter().len(), btree.len());
+
+        for (k, v) in svmap.iter_mut() { *v ^= *k; }
+        for (k, v) in btree.iter_mut() { *v ^= *k; }
+        for v in svmap.values_mut().rev() { *v += 1; }
+        for v in btree.values_mut().rev() { *v += 1; }
+
+        let mut a = svmap.iter();
+        let mut b = btree.iter();
+
+        loop {
+            let x = a.next();
+            assert_eq!(x, b.next());
+            let y = a.next_back();
+            assert_eq!(y, b.next_back());
+            assert_eq!(a.len(), b.len());
+
+            if x.is_none() || y.is_none() { break; }
+        }
+
+        assert!(svmap.clone().into_iter().eq(btree.clone().into_iter()));
+        assert!(svmap.into_iter().rev().eq(btree.into_iter().rev()));
+    }
+}
+
+
+#[test]
+fn test_range() {
+    use rand::{ Rng, SeedableRng };
+    use std::ops::Bound::*;
+    
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+    let mut svmap = SVMap::<i32,i32>::new();
+    let mut btree = BTreeMap::<i32,i32>::new();
+
+    for i in 0..1000 {
+        let k = rng.gen_range(0, 200);
+        let v = rng.gen();
+
+        if rng.gen_range(0, 10) < 7 {
+            svmap.insert(k, v);
+            btree.insert(k, v);
+        } else {
+            svmap.remove(&k);
+            btree.remove(&k);
+        }
+
+        let a = rng.gen_range(-10, 210);
+        let b = rng.gen_range(a, 220);
+
+        assert!(svmap.range(a..b).eq(btree.range(a..b)));
+        assert!(svmap.range(a..=b).rev().eq(btree.range(a..=b).rev()));
+        assert!(svmap.range(..b).eq(btree.range(..b)));
+        assert!(svmap.range(a..).rev().eq(btree.range(a..).rev()));
+        assert!(svmap.range((Excluded(a), Included(b))).eq(btree.range((Excluded(a), Included(b)))));
+
+        if i % 10 == 0 {
+            for (_, v) in svmap.range_mut(a..b) { *v = v.wrapping_mul(3); }
+            for (_, v) in btree.range_mut(a..b) { *v = v.wrapping_mul(3); }
+
+            assert!(svmap.iter().eq(btree.iter()));
+        }
+    }
+
+    let mut svmap = SVMap::<String,i32>::new();
+    svmap.insert("b".to_string(), 1);
+    svmap.insert("d".to_string(), 2);
+
+    let keys: Vec<_> = svmap.range::<str, _>((Included("a"), Excluded("c"))).collect();
+    assert_eq!(keys, vec![(&"b".to_string(), &1)]);
+}
+
+#[test]
+#[should_panic]
+fn test_range_panic() {
+    let svmap = SVMap::<i32,i32>::new();
+    #[allow(clippy::reversed_empty_ranges)]
+    svmap.range(5..3).count();
+}
+
+
+#[test]
+fn test_entry() {
+    use rand::{ Rng, SeedableRng };
+    use dynamization::sorted_vec::Entry;
+    use std::collections::btree_map;
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    let mut svmap = SVMap::<i32,i32>::new();
+    let mut btree = BTreeMap::<i32,i32>::new();
+
+    for _ in 0..10000 {
+        let k = rng.gen_range(0, 200);
+        let v = rng.gen_range(0, 1000);
+
+        match rng.gen_range(0, 6) {
+            0 => {
+                assert_eq!(
+                    *svmap.entry(k).or_insert(v),
+                    *btree.entry(k).or_insert(v)
+                );
+            }
+
+            1 => {
+                let sv = svmap.entry(k).and_modify(|x| *x += 1).or_insert_with(|| v);
+                let bt = btree.entry(k).and_modify(|x| *x += 1).or_insert_with(|| v);
+                assert_eq!(*sv, *bt);
+            }
+
+            2 => {
+                let sv = match svmap.entry(k) {
+                    Entry::Occupied(entry) => Some(entry.remove_entry()),
+                    Entry::Vacant(_) => None,
+                };
+                let bt = match btree.entry(k) {
+                    btree_map::Entry::Occupied(entry) => Some(entry.remove_entry()),
+                    btree_map::Entry::Vacant(_) => None,
+                };
+                assert_eq!(sv, bt);
+            }
+
+            3 => {
+                if let Entry::Occupied(entry) = svmap.entry(k) {
+                    assert_eq!(entry.remove(), btree.remove(&k).unwrap());
+                }
+            }
+
+            _ => {
+                assert_eq!(svmap.remove(&k), btree.remove(&k));
+            }
+        }
+
+        assert_eq!(svmap.len(), btree.len());
+    }
+
+    assert!(svmap.iter().eq(btree.iter()));
+
+    for ref k in 0..200 {
+        assert_eq!(svmap.get(k), btree.get(k));
+    }
+}
+
+
+#[test]
+fn test_rank_select() {
+    use rand::{ Rng, SeedableRng };
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+    for &size in &[0, 1, 10, 100, 1000, 3000] {
+        let mut svmap = SVMap::<i32,i32>::new();
+        let mut btree = BTreeMap::<i32,i32>::new();
+
+        for _ in 0..size {
+            let k = rng.gen_range(0, 1000);
+
+            if rng.gen_range(0, 10) < 7 {
+                assert_eq!(svmap.insert(k, -k), btree.insert(k, -k));
+            } else {
+                assert_eq!(svmap.remove(&k), btree.remove(&k));
+            }
+        }
+
+        for k in -1..=1000 {
+            assert_eq!(svmap.rank(&k), btree.range(..k).count());
+        }
+
+        for (n, (k, v)) in btree.iter().enumerate() {
+            assert_eq!(svmap.select(n), Some(k));
+            assert_eq!(svmap.nth(n), Some((k, v)));
+        }
+
+        assert_eq!(svmap.select(btree.len()), None);
+    }
+}
+
+
+#[test]
+fn test_neighbors() {
+    use rand::{ Rng, SeedableRng };
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    let mut svmap = SVMap::<i32,i32>::new();
+    let mut btree = BTreeMap::<i32,i32>::new();
+
+    for _ in 0..3000 {
+        let k = rng.gen_range(0, 1000);
+
+        match rng.gen_range(0, 10) {
+            0 => assert_eq!(svmap.pop_first(), btree.pop_first()),
+            1 => assert_eq!(svmap.pop_last(), btree.pop_last()),
+            2 | 3 => assert_eq!(svmap.remove(&k), btree.remove(&k)),
+            _ => assert_eq!(svmap.insert(k, -k), btree.insert(k, -k)),
+        }
+
+        assert_eq!(svmap.len(), btree.len());
+        assert_eq!(svmap.first_key_value(), btree.first_key_value());
+        assert_eq!(svmap.last_key_value(), btree.last_key_value());
+
+        let k = rng.gen_range(-1, 1001);
+        assert_eq!(svmap.floor(&k), btree.range(..=k).next_back());
+        assert_eq!(svmap.ceiling(&k), btree.range(k..).next());
+    }
+
+    if let Some(mut entry) = svmap.first_entry() {
+        *entry.get_mut() += 1;
+    }
+
+    assert_eq!(svmap.first_key_value().map(|(_, v)| *v), btree.first_key_value().map(|(_, v)| v + 1));
+
+    let mut svmap: SVMap<i32, i32> = (0..10000).map(|x| (x, -x)).collect();
+    let mut drained = Vec::new();
+
+    while let Some((k, _)) = svmap.pop_first() {
+        drained.push(k);
+
+        if let Some((k, _)) = svmap.pop_last() {
+            drained.push(k);
+        }
+    }
+
+    drained.sort_unstable();
+    assert!(drained.into_iter().eq(0..10000));
+    assert!(svmap.is_empty());
+}
+
+
+#[test]
+fn test_append() {
+    use rand::{ Rng, SeedableRng };
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+    for &size in &[0, 1, 10, 100, 1000] {
+        let mut maps = Vec::new();
+
+        for _ in 0..2 {
+            let mut svmap = SVMap::<i32,i32>::new();
+            let mut btree = BTreeMap::<i32,i32>::new();
+
+            for _ in 0..size {
+                let k = rng.gen_range(0, 300);
+
+                if rng.gen_range(0, 10) < 7 {
+                    svmap.insert(k, rng.gen_range(0, 100));
+                    btree.insert(k, *svmap.get(&k).unwrap());
+                } else {
+                    svmap.remove(&k);
+                    btree.remove(&k);
+                }
+            }
+
+            maps.push((svmap, btree));
+        }
+
+        let (mut b, b_btree) = maps.pop().unwrap();
+        let (mut a, a_btree) = maps.pop().unwrap();
+
+        let joined: Vec<_> = a.merge_join(&b).collect();
+        let mut expected = Vec::new();
+
+        for k in 0..300 {
+            let (x, y) = (a_btree.get(&k), b_btree.get(&k));
+
+            if x.is_some() || y.is_some() {
+                expected.push((k, x, y));
+            }
+        }
+
+        assert_eq!(joined.into_iter().map(|(k, x, y)| (*k, x, y)).collect::<Vec<_>>(), expected);
+
+        let mut sums = a.clone();
+        sums.append_with(&mut b.clone(), |_, x, y| x + y);
+
+        a.append(&mut b);
+        assert!(b.is_empty());
+        assert_eq!(b.iter().count(), 0);
+
+        let mut union = a_btree.clone();
+        union.extend(b_btree.iter());
+        assert_eq!(a.len(), union.len());
+        assert!(a.iter().eq(union.iter()));
+
+        for k in union.keys() {
+            let sum = a_btree.get(k).unwrap_or(&0) + b_btree.get(k).unwrap_or(&0);
+            assert_eq!(sums.get(k), Some(&sum));
+        }
+
+        assert_eq!(sums.len(), union.len());
+
+        // The map stays consistent after appending.
+        for k in 0..300 {
+            assert_eq!(a.remove(&k), union.remove(&k));
+            assert_eq!(a.len(), union.len());
+        }
+    }
+}
+
+
+#[test]
+fn test_bulk_remove() {
+    test_bulk_remove_strategy::<strategy::Binary>();
+    test_bulk_remove_strategy::<strategy::SimpleBinary>();
+    test_bulk_remove_strategy::<strategy::SkewBinary>();
+}
+
+fn test_bulk_remove_strategy<S: strategy::Strategy>() {
+    use rand::{ Rng, SeedableRng };
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    let mut svmap = SVMap::<i32,i32,S>::default();
+    let mut btree = BTreeMap::<i32,i32>::new();
+
+    for _ in 0..5000 {
+        let k = rng.gen_range(0, 2000);
+
+        match rng.gen_range(0, 100) {
+            0 => {
+                let (a, b) = (rng.gen_range(0, 2000), rng.gen_range(0, 2000));
+                let (a, b) = (a.min(b), a.max(b));
+                let expected = btree.range(a..b).count();
+                btree.retain(|k, _| !(a..b).contains(k));
+
+                assert_eq!(svmap.remove_range(a..b), expected);
+            }
+
+            1 => {
+                let m = rng.gen_range(2, 10);
+                btree.retain(|k, v| k % m != 0 || *v % 2 == 0);
+                svmap.retain(|k, v| k % m != 0 || *v % 2 == 0);
+            }
+
+            2 => {
+                let m = rng.gen_range(2, 10);
+                let mut expected: Vec<_> = btree.iter()
+                    .filter(|(k, _)| *k % m == 1)
+                    .map(|(k, v)| (*k, *v))
+                    .collect();
+
+                btree.retain(|k, _| k % m != 1);
+
+                let mut extracted: Vec<_> = svmap.extract_if(|k, _| k % m == 1).collect();
+                extracted.sort();
+                expected.sort();
+
+                assert_eq!(extracted, expected);
+            }
+
+            3 => {
+                // Stops early: the rest stays in the map.
+                let taken: Vec<_> = svmap.extract_if(|k, _| k % 3 == 0).take(10).collect();
+
+                for (k, v) in taken {
+                    assert_eq!(btree.remove(&k), Some(v));
+                }
+            }
+
+            4..=20 => assert_eq!(svmap.remove(&k), btree.remove(&k)),
+            _ => assert_eq!(svmap.insert(k, rng.gen()), btree.insert(k, *svmap.get(&k).unwrap())),
+        }
+
+        assert_eq!(svmap.len(), btree.len());
+
+        let k = rng.gen_range(0, 2000);
+        assert_eq!(svmap.get(&k), btree.get(&k));
+        assert_eq!(svmap.rank(&k), btree.range(..k).count());
+        assert_eq!(svmap.ceiling(&k), btree.range(k..).next());
+    }
+
+    assert!(svmap.iter().eq(btree.iter()));
+    assert!(svmap.iter_mut().map(|(k, v)| (k, &*v)).eq(btree.iter()));
+    assert!(svmap.range(500..1500).rev().eq(btree.range(500..1500).rev()));
+
+    for (n, kv) in btree.iter().enumerate() {
+        assert_eq!(svmap.nth(n), Some(kv));
+    }
+
+    assert!(svmap.into_iter().eq(btree.into_iter()));
+}
+
+
+#[test]
+fn test_rebuild_policy() {
+    use rand::{ Rng, SeedableRng };
+
+    let policies = [
+        RebuildPolicy::default(),
+        RebuildPolicy::Fraction(0.1),
+        RebuildPolicy::Count(0),
+        RebuildPolicy::Count(100),
+        RebuildPolicy::PerUnit(0.25),
+        RebuildPolicy::Never,
+    ];
+
+    for &policy in &policies {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut svmap = SVMap::<i32,i32>::new();
+        let mut btree = BTreeMap::<i32,i32>::new();
+
+        svmap.set_rebuild_policy(policy);
+
+        for _ in 0..5000 {
+            let k = rng.gen_range(0, 1000);
+
+            match rng.gen_range(0, 100) {
+                0 => {
+                    let a = rng.gen_range(0, 1000);
+                    btree.retain(|k, _| !(a..a + 100).contains(k));
+                    svmap.remove_range(a..a + 100);
+                }
+
+                1..=40 => assert_eq!(svmap.remove(&k), btree.remove(&k)),
+                _ => assert_eq!(svmap.insert(k, -k), btree.insert(k, -k)),
+            }
+
+            assert_eq!(svmap.len(), btree.len());
+            assert_eq!(svmap.get(&k), btree.get(&k));
+        }
+
+        assert_eq!(svmap.rebuild_policy(), policy);
+        assert!(svmap.iter().eq(btree.iter()));
+
+        for (n, kv) in btree.iter().enumerate() {
+            assert_eq!(svmap.nth(n), Some(kv));
+        }
+    }
+}
+
+
+#[test]
+fn test_get_many() {
+    use rand::{ Rng, SeedableRng };
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+    for &size in &[0, 1, 10, 100, 1000, 10000] {
+        let mut svmap = SVMap::<i32,i32,strategy::Binary,Bloom10>::default();
+
+        for _ in 0..size {
+            let k = rng.gen_range(0, 1000);
+
+            if rng.gen_range(0, 10) < 7 {
+                svmap.insert(k, -k);
+            } else {
+                svmap.remove(&k);
+            }
+        }
+
+        if size > 0 {
+            let a = rng.gen_range(0, 1000);
+            svmap.remove_range(a..a + 50);
+        }
+
+        for &batch in &[0, 1, 10, 100, 2000] {
+            let keys: Vec<i32> = (0..batch).map(|_| rng.gen_range(-10, 1010)).collect();
+            let expected: Vec<_> = keys.iter().map(|k| svmap.get(k)).collect();
+
+            assert_eq!(svmap.get_many(&keys), expected);
+        }
+    }
+}
+
+
+#[test]
+fn test_traits() {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{ Hash, Hasher };
+
+    fn hash<T: Hash>(x: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        x.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    let pairs = [(3, 'c'), (1, 'a'), (2, 'b'), (1, 'x')];
+    let svmap = SVMap::<i32,char>::from(pairs);
+    let btree = BTreeMap::from(pairs);
+
+    assert_eq!(svmap.len(), 3);
+    assert_eq!(svmap[&1], 'x');
+    assert_eq!(format!("{:?}", svmap), format!("{:?}", btree));
+
+    // Same contents with different units and tombstones
+    let mut other = SVMap::<i32,char>::new();
+
+    for k in 0..100 {
+        other.insert(k, '-');
+    }
+
+    other.retain(|k, _| (1..=3).contains(k));
+    other.extend([(1, 'a'), (2, 'b'), (3, 'c')]);
+    assert_ne!(svmap, other);
+
+    other.extend([(&1, &'x')]);
+    assert_eq!(svmap, other);
+    assert_eq!(hash(&svmap), hash(&other));
+
+    other.insert(4, 'd');
+    assert!(svmap < other);
+    assert_ne!(hash(&svmap), hash(&other));
+
+    let mut extended = svmap.clone();
+    extended.extend((0..10).map(|k| (k, 'e')));
+    assert_eq!(extended.len(), 10);
+    assert!(extended.values().all(|&v| v == 'e'));
+}
+
+#[test]
+#[should_panic(expected = "no entry found for key")]
+fn test_index_panic() {
+    let svmap = SVMap::<i32,i32>::from([(1, 1)]);
+    let _ = svmap[&2];
+}
+
+
+#[test]
+fn test_replace_key() {
+    use std::cmp::Ordering;
+
+    // Compared by the name only
+    #[derive(Debug)]
+    struct Key(&'static str, u32);
+
+    impl PartialEq for Key {
+        fn eq(&self, other: &Self) -> bool { self.0 == other.0 }
+    }
+
+    impl Eq for Key {}
+
+    impl PartialOrd for Key {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+    }
+
+    impl Ord for Key {
+        fn cmp(&self, other: &Self) -> Ordering { self.0.cmp(other.0) }
+    }
+
+    let mut svmap = SVMap::<Key,i32>::new();
+
+    for (n, name) in ["a", "b", "c"].iter().enumerate() {
+        svmap.insert(Key(name, 0), n as i32);
+    }
+
+    assert_eq!(svmap.insert(Key("a", 1), 10), Some(0));
+    assert_eq!(svmap.get_key_value(&Key("a", 9)).map(|(k, v)| (k.1, *v)), Some((0, 10)));
+
+    let (old_key, old_value) = svmap.insert_replace_key(Key("a", 2), 20).unwrap();
+    assert_eq!((old_key.1, old_value), (0, 10));
+    assert_eq!(svmap.get_key_value(&Key("a", 9)).map(|(k, v)| (k.1, *v)), Some((2, 20)));
+
+    assert_eq!(svmap.replace_key(Key("b", 3)).map(|k| k.1), Some(0));
+    assert_eq!(svmap.get_key_value(&Key("b", 9)).map(|(k, v)| (k.1, *v)), Some((3, 1)));
+    assert!(svmap.replace_key(Key("d", 4)).is_none());
+    assert!(!svmap.contains_key(&Key("d", 4)));
+
+    // Absent and removed keys are inserted.
+    svmap.remove(&Key("c", 9));
+    assert!(svmap.insert_replace_key(Key("c", 5), 50).is_none());
+    assert!(svmap.insert_replace_key(Key("d", 6), 60).is_none());
+    assert_eq!(svmap.len(), 4);
+
+    if let Some((key, value)) = svmap.get_key_value_mut(&Key("c", 9)) {
+        assert_eq!(key.1, 5);
+        *value += 1;
+    }
+
+    assert_eq!(svmap.get(&Key("c", 9)), Some(&51));
+    assert!(svmap.get_key_value_mut(&Key("e", 9)).is_none());
+}
+
+
+#[test]
+fn test_prefix() {
+    use rand::{ Rng, SeedableRng };
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    let mut strings = SVMap::<String,i32>::new();
+    let mut bytes = SVMap::<Vec<u8>,i32>::new();
+    let mut btree = BTreeMap::<String,i32>::new();
+
+    for step in 0..3000 {
+        let len = rng.gen_range(0, 5);
+        let key: String = (0..len).map(|_| ['a', 'b', 'c'][rng.gen_range(0, 3)]).collect();
+
+        if rng.gen_range(0, 10) < 8 {
+            strings.insert(key.clone(), step);
+            bytes.insert(key.clone().into_bytes(), step);
+            btree.insert(key, step);
+        } else {
+            strings.remove(&key);
+            bytes.remove(key.as_bytes());
+            btree.remove(&key);
+        }
+    }
+
+    for prefix in &["", "a", "ab", "cab", "abcab"] {
+        let expected: Vec<_> = btree.iter().filter(|(k, _)| k.starts_with(prefix)).collect();
+
+        assert!(strings.prefix(*prefix).eq(expected.iter().cloned()));
+        assert!(strings.prefix(*prefix).rev().eq(expected.iter().rev().cloned()));
+
+        assert!(bytes.prefix(prefix.as_bytes())
+            .map(|(k, v)| (k.as_slice(), v))
+            .eq(expected.iter().map(|(k, v)| (k.as_bytes(), *v))));
+    }
+}
+
+
+#[test]
+fn test_comparator() {
+    use dynamization::sorted_vec::{ SVMapBy, Reversed };
+    use rand::{ Rng, SeedableRng };
+    use std::cmp::Reverse;
+    
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+    let mut svmap = SVMapBy::<i32, i32, Reversed>::new();
+    let mut btree = BTreeMap::<Reverse<i32>, i32>::new();
+
+    for _ in 0..3000 {
+        let k = rng.gen_range(0, 100);
+        let v = rng.gen_range(0, 1000);
+
+        match rng.gen_range(0, 100) {
+            0 => {
+                let a = rng.gen_range(0, 100);
+                let len = btree.len();
+                btree.retain(|k, _| !(a - 10 < k.0 && k.0 <= a));
+                assert_eq!(svmap.remove_range(a..a - 10), len - btree.len());
+            }
+
+            1..=30 => assert_eq!(svmap.remove(&k), btree.remove(&Reverse(k))),
+
+            31..=50 => {
+                *svmap.entry(k).or_insert(0) += v;
+                *btree.entry(Reverse(k)).or_insert(0) += v;
+            }
+
+            _ => assert_eq!(svmap.insert(k, v), btree.insert(Reverse(k), v)),
+        }
+
+        assert_eq!(svmap.len(), btree.len());
+    }
+
+    for k in 0..100 {
+        assert_eq!(svmap.get(&k), btree.get(&Reverse(k)));
+        assert_eq!(svmap.contains_key(&k), btree.contains_key(&Reverse(k)));
+    }
+
+    assert!(svmap.iter().eq(btree.iter().map(|(k, v)| (&k.0, v))));
+    // The range is reversed as well.
+    let (high, low) = (80, 20);
+    assert!(svmap.range(high..=low).eq(btree.range(Reverse(high)..=Reverse(low)).map(|(k, v)| (&k.0, v))));
+    assert!(svmap.keys().eq(btree.keys().map(|k| &k.0)));
+    assert_eq!(svmap.first_key_value(), btree.iter().next().map(|(k, v)| (&k.0, v)));
+
+    for (_, v) in svmap.range_mut(50..) {
+        *v += 1;
+    }
+
+    for (_, v) in btree.range_mut(Reverse(50)..) {
+        *v += 1;
+    }
+
+    for (_, v) in svmap.iter_mut() {
+        *v *= 2;
+    }
+
+    for v in btree.values_mut() {
+        *v *= 2;
+    }
+
+    assert!(svmap.values().eq(btree.values()));
+    let last = btree.keys().next_back().cloned();
+    assert_eq!(svmap.pop_last(), last.and_then(|k| btree.remove(&k).map(|v| (k.0, v))));
+    assert!(svmap.iter().eq(btree.iter().map(|(k, v)| (&k.0, v))));
+}
+
+
+#[test]
+fn test_extend() {
+    test_extend_strategy::<strategy::Binary, NoFilter>();
+    test_extend_strategy::<strategy::SkewBinary, Bloom10>();
+}
+
+fn test_extend_strategy<S: strategy::Strategy, F: Filter<i32>>() {
+    use rand::{ Rng, SeedableRng };
+    
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+    let mut svmap = SVMap::<i32,i32,S,F>::default();
+    let mut btree = BTreeMap::<i32,i32>::new();
+
+    for step in 0..300 {
+        match rng.gen_range(0, 4) {
+            0 => {
+                let batch: Vec<(i32, i32)> = (0..rng.gen_range(0, 50))
+                    .map(|_| (rng.gen_range(0, 500), rng.gen()))
+                    .collect();
+
+                svmap.extend(batch.iter().cloned());
+                btree.extend(batch);
+            }
+
+            1 => {
+                let start = rng.gen_range(0, 500);
+                let end = start + rng.gen_range(0, 50);
+
+                svmap.remove_range(start..end);
+                btree.retain(|k, _| !(start..end).contains(k));
+            }
+
+            _ => {
+                let k = rng.gen_range(0, 500);
+
+                if step % 3 == 0 {
+                    assert_eq!(svmap.remove(&k), btree.remove(&k));
+                } else {
+                    assert_eq!(svmap.insert(k, step), btree.insert(k, step));
+                }
+            }
+        }
+
+        assert_eq!(svmap.len(), btree.len());
+        assert!(svmap.iter().eq(btree.iter()));
+    }
+}
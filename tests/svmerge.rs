@@ -0,0 +1,72 @@
+use dynamization::sorted_vec::{ SVMergeMap, Sum, Concat };
+use std::collections::BTreeMap;
+
+
+#[test]
+fn test_concat() {
+    use rand::{ Rng, SeedableRng };
+    
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+    for &size in &[0, 1, 2, 3, 4, 5, 10, 100, 1000, 5000] {
+        let mut map = SVMergeMap::<i32, Vec<u32>, Concat>::new();
+        let mut btree = BTreeMap::<i32, Vec<u32>>::new();
+
+        for step in 0..size {
+            let k = rng.gen_range(0, 50);
+
+            match rng.gen_range(0, 10) {
+                0 => {
+                    map.insert(k, vec![step]);
+                    btree.insert(k, vec![step]);
+                }
+
+                1 => {
+                    map.remove(k);
+                    btree.remove(&k);
+                }
+
+                _ => {
+                    map.merge(k, vec![step]);
+                    btree.entry(k).or_default().push(step);
+                }
+            }
+
+            let k = rng.gen_range(0, 50);
+            assert_eq!(map.get(&k).as_ref(), btree.get(&k));
+            assert_eq!(map.contains_key(&k), btree.contains_key(&k));
+        }
+
+        for k in 0..50 {
+            assert_eq!(map.get(&k).as_ref(), btree.get(&k));
+        }
+
+        map.compact();
+        assert_eq!(map.stored_len(), btree.len());
+
+        for k in 0..50 {
+            assert_eq!(map.get(&k).as_ref(), btree.get(&k));
+        }
+    }
+}
+
+
+#[test]
+fn test_counters() {
+    let mut counts = SVMergeMap::<u32, u64, Sum>::new();
+
+    for x in 0..10000 {
+        counts.merge(x % 100, 1);
+    }
+
+    // The merges combine the operands.
+    assert!(counts.stored_len() < 1000);
+
+    for x in 0..100 {
+        assert_eq!(counts.get(&x), Some(100));
+    }
+
+    counts.insert(7, 0);
+    counts.merge(7, 5);
+    assert_eq!(counts.get(&7), Some(5));
+}
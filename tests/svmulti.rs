@@ -0,0 +1,116 @@
+use dynamization::sorted_vec::{ SVMultiMap, SVBag };
+use std::collections::BTreeMap;
+
+
+#[test]
+fn test_multimap() {
+    use rand::{ Rng, SeedableRng };
+    
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+    for &size in &[0, 1, 2, 3, 4, 5, 10, 100, 1000, 5000] {
+        let mut multimap = SVMultiMap::<i32, u32>::new();
+        let mut btree = BTreeMap::<i32, Vec<u32>>::new();
+
+        for step in 0..size {
+            let k = rng.gen_range(0, 50);
+
+            match rng.gen_range(0, 10) {
+                0 => {
+                    let mut removed = multimap.remove_all(&k);
+                    removed.sort();
+                    let mut expected = btree.remove(&k).unwrap_or_default();
+                    expected.sort();
+                    assert_eq!(removed, expected);
+                }
+
+                1 | 2 => {
+                    let removed = multimap.remove_one(&k);
+                    let values = btree.entry(k).or_default();
+
+                    match removed {
+                        None => assert!(values.is_empty()),
+
+                        Some(v) => {
+                            let pos = values.iter().position(|&x| x == v).unwrap();
+                            values.swap_remove(pos);
+                        }
+                    }
+
+                    if values.is_empty() { btree.remove(&k); }
+                }
+
+                _ => {
+                    multimap.insert(k, step);
+                    btree.entry(k).or_default().push(step);
+                }
+            }
+
+            assert_eq!(multimap.len(), btree.values().map(Vec::len).sum::<usize>());
+
+            let k = rng.gen_range(-1, 51);
+            let mut values: Vec<u32> = multimap.get_all(&k).cloned().collect();
+            values.sort();
+            let mut expected = btree.get(&k).cloned().unwrap_or_default();
+            expected.sort();
+
+            assert_eq!(values, expected);
+            assert_eq!(multimap.count(&k), expected.len());
+            assert_eq!(multimap.contains_key(&k), !expected.is_empty());
+        }
+
+        let keys: Vec<i32> = multimap.iter().map(|(k, _)| *k).collect();
+        let expected: Vec<i32> = btree.iter().flat_map(|(k, v)| v.iter().map(move |_| *k)).collect();
+        assert_eq!(keys, expected);
+    }
+}
+
+
+#[test]
+fn test_bag() {
+    use rand::{ Rng, SeedableRng };
+    
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+    for &size in &[0, 1, 2, 3, 4, 5, 10, 100, 1000, 5000] {
+        let mut bag = SVBag::<i32>::new();
+        let mut counts = BTreeMap::<i32, usize>::new();
+
+        for _ in 0..size {
+            let x = rng.gen_range(0, 30);
+
+            match rng.gen_range(0, 10) {
+                0 => {
+                    assert_eq!(bag.remove_all(&x), counts.remove(&x).unwrap_or(0));
+                }
+
+                1 | 2 => {
+                    let expected = match counts.get_mut(&x) {
+                        None => false,
+
+                        Some(count) => {
+                            *count -= 1;
+                            if *count == 0 { counts.remove(&x); }
+                            true
+                        }
+                    };
+
+                    assert_eq!(bag.remove_one(&x), expected);
+                }
+
+                _ => {
+                    bag.insert(x);
+                    *counts.entry(x).or_default() += 1;
+                }
+            }
+
+            assert_eq!(bag.len(), counts.values().sum::<usize>());
+
+            let y = rng.gen_range(-1, 31);
+            assert_eq!(bag.count(&y), counts.get(&y).cloned().unwrap_or(0));
+            assert_eq!(bag.contains(&y), counts.contains_key(&y));
+        }
+
+        assert!(bag.iter().eq(counts.iter().map(|(x, n)| (x, *n))));
+    }
+}
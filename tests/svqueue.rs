@@ -31,7 +31,7 @@ fn test_sorted_strategy<S: strategy::Strategy>() {
             svqueue.push(x);
         }
 
-        while svqueue.len() > 0 {
+        while !svqueue.is_empty() {
             result.push(svqueue.pop().unwrap());
         }
 
@@ -49,13 +49,13 @@ fn test_binheap() {
     test_binheap_strategy::<strategy::SkewBinary>();
 }
 
-fn test_binheap_strategy<S: strategy::Strategy>() {
+fn test_binheap_strategy<S: strategy::Strategy + Clone>() {
     use rand::{ Rng, SeedableRng };
     
     let mut rng = rand::rngs::StdRng::seed_from_u64(42);
 
     for &size in &[0, 1, 2, 3, 4, 5, 10, 100, 1000] {
-        let mut svqueue = SVQueue::<i32>::new();
+        let mut svqueue = SVQueue::<i32>::with_strategy::<S>();
         let mut bin_heap = BinaryHeap::<i32>::new();
 
         for i in 0..size {
@@ -74,3 +74,118 @@ fn test_binheap_strategy<S: strategy::Strategy>() {
     }
 }
 
+
+
+#[test]
+fn test_min_queue() {
+    use dynamization::sorted_vec::{ SVMinQueue, SVQueueBy, Compare };
+    use rand::{ Rng, SeedableRng };
+    use std::cmp::Ordering;
+
+    struct ByAbs;
+
+    impl Compare<i32> for ByAbs {
+        fn compare(a: &i32, b: &i32) -> Ordering {
+            a.abs().cmp(&b.abs())
+        }
+    }
+    
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+    for &size in &[0, 1, 2, 3, 4, 5, 10, 100, 1000] {
+        let to_process: Vec<i32> = (0..size).map(|_| rng.gen_range(-1000, 1000)).collect();
+
+        let mut min_queue = SVMinQueue::with_strategy::<strategy::SkewBinary>();
+        let mut abs_queue = SVQueueBy::<i32, ByAbs>::new();
+
+        for &x in &to_process {
+            min_queue.push(x);
+            abs_queue.push(x);
+        }
+
+        let mut to_sort = to_process.clone();
+        to_sort.sort();
+
+        let mut result = Vec::new();
+
+        while let Some(x) = min_queue.pop() {
+            result.push(x);
+        }
+
+        assert_eq!(to_sort, result);
+
+        let mut abs_sorted: Vec<i32> = to_process.iter().map(|x| x.abs()).collect();
+        abs_sorted.sort();
+        abs_sorted.reverse();
+
+        // The comparator needs neither `Clone` nor `Debug`.
+        let mut abs_copy = abs_queue.clone();
+        assert_eq!(format!("{:?}", abs_copy), format!("{:?}", abs_queue));
+
+        let mut result = Vec::new();
+
+        while let Some(x) = abs_queue.pop() {
+            result.push(x.abs());
+        }
+
+        assert_eq!(abs_sorted, result);
+        assert_eq!(abs_copy.pop().map(i32::abs), abs_sorted.first().copied());
+    }
+}
+
+
+
+#[test]
+fn test_peek_mut() {
+    test_peek_mut_strategy::<strategy::Binary>();
+    test_peek_mut_strategy::<strategy::SimpleBinary>();
+    test_peek_mut_strategy::<strategy::SkewBinary>();
+}
+
+fn test_peek_mut_strategy<S: strategy::Strategy>() {
+    use dynamization::sorted_vec::SVQueuePeekMut;
+    use std::collections::binary_heap::PeekMut;
+    use rand::{ Rng, SeedableRng };
+    
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+    let mut svqueue = SVQueue::<i32>::with_strategy::<S>();
+    let mut bin_heap = BinaryHeap::<i32>::new();
+
+    for _ in 0..3000 {
+        let x = rng.gen_range(0, 1000);
+
+        match rng.gen_range(0, 10) {
+            0..=5 => {
+                svqueue.push(x);
+                bin_heap.push(x);
+            }
+
+            6 | 7 => {
+                if let Some(mut top) = svqueue.peek_mut() { *top -= x; }
+                if let Some(mut top) = bin_heap.peek_mut() { *top -= x; }
+            }
+
+            8 => {
+                assert_eq!(
+                    svqueue.peek_mut().map(SVQueuePeekMut::pop),
+                    bin_heap.peek_mut().map(PeekMut::pop),
+                );
+            }
+
+            _ => {
+                if let Some(top) = svqueue.peek_mut() { std::mem::forget(top); }
+                bin_heap.pop();
+            }
+        }
+
+        assert_eq!(svqueue.len(), bin_heap.len());
+        assert_eq!(svqueue.peek(), bin_heap.peek());
+    }
+
+    while let Some(x) = svqueue.pop() {
+        assert_eq!(Some(x), bin_heap.pop());
+    }
+
+    assert!(bin_heap.is_empty());
+}
@@ -0,0 +1,121 @@
+use dynamization::sorted_vec::{ SVSet, RebuildPolicy };
+use dynamization::strategy;
+use std::collections::BTreeSet;
+
+
+#[test]
+fn test_set() {
+    test_set_strategy::<strategy::Binary>();
+    test_set_strategy::<strategy::SkewBinary>();
+}
+
+fn test_set_strategy<S: strategy::Strategy>() {
+    use rand::{ Rng, SeedableRng };
+    
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+    for &size in &[0, 1, 2, 3, 4, 5, 10, 100, 1000, 5000] {
+        let mut svset = SVSet::<i32,S>::default();
+        let mut btree = BTreeSet::<i32>::new();
+
+        for _ in 0..size {
+            let x = rng.gen_range(0, 100);
+
+            if rng.gen_range(0, 10) < 7 {
+                assert_eq!(svset.insert(x), btree.insert(x));
+            } else {
+                assert_eq!(svset.remove(&x), btree.remove(&x));
+            }
+
+            assert_eq!(svset.len(), btree.len());
+
+            let y = rng.gen_range(-1, 101);
+            assert_eq!(svset.contains(&y), btree.contains(&y));
+        }
+
+        assert!(svset.iter().eq(btree.iter()));
+        assert!(svset.iter().rev().eq(btree.iter().rev()));
+        assert!(svset.range(10..=50).eq(btree.range(10..=50)));
+        assert!(svset.range(..30).rev().eq(btree.range(..30).rev()));
+        assert_eq!(svset.first(), btree.iter().next());
+        assert_eq!(svset.last(), btree.iter().next_back());
+
+        for x in -1..=100 {
+            assert_eq!(svset.rank(&x), btree.range(..x).count());
+        }
+
+        for (n, x) in btree.iter().enumerate() {
+            assert_eq!(svset.select(n), Some(x));
+        }
+
+        assert_eq!(svset.select(btree.len()), None);
+        assert!(svset.into_iter().eq(btree.into_iter()));
+    }
+}
+
+
+#[test]
+fn test_rebuild_policy() {
+    use rand::{ Rng, SeedableRng };
+
+    let policies = [
+        RebuildPolicy::default(),
+        RebuildPolicy::Count(0),
+        RebuildPolicy::PerUnit(0.25),
+        RebuildPolicy::Never,
+    ];
+
+    for &policy in &policies {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut svset: SVSet<i32> = (0..1000).collect();
+        let mut btree: BTreeSet<i32> = (0..1000).collect();
+
+        svset.set_rebuild_policy(policy);
+
+        for _ in 0..5000 {
+            let x = rng.gen_range(0, 2000);
+
+            match rng.gen_range(0, 10) {
+                0..=2 => assert_eq!(svset.take(&x), btree.take(&x)),
+                3..=5 => assert_eq!(svset.remove(&x), btree.remove(&x)),
+                _ => assert_eq!(svset.insert(x), btree.insert(x)),
+            }
+
+            assert_eq!(svset.len(), btree.len());
+        }
+
+        assert_eq!(svset.rebuild_policy(), policy);
+        assert!(svset.iter().eq(btree.iter()));
+        assert!(svset.range(500..1500).eq(btree.range(500..1500)));
+
+        for (n, x) in btree.iter().enumerate() {
+            assert_eq!(svset.select(n), Some(x));
+            assert_eq!(svset.rank(x), n);
+        }
+    }
+}
+
+
+#[test]
+fn test_set_ops() {
+    use rand::{ Rng, SeedableRng };
+    
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+    let a: BTreeSet<i32> = (0..500).map(|_| rng.gen_range(0, 1000)).collect();
+    let b: BTreeSet<i32> = (0..500).map(|_| rng.gen_range(0, 1000)).collect();
+
+    let sa: SVSet<i32> = a.iter().cloned().collect();
+    let sb: SVSet<i32> = b.iter().cloned().collect();
+    let vb: Vec<i32> = b.iter().cloned().collect();
+
+    assert!(sa.union(&sb).eq(a.union(&b)));
+    assert!(sa.intersection(&sb).eq(a.intersection(&b)));
+    assert!(sa.difference(&sb).eq(a.difference(&b)));
+    assert!(sa.symmetric_difference(&sb).eq(a.symmetric_difference(&b)));
+
+    assert!(sa.union(&vb).eq(a.union(&b)));
+    assert!(sa.intersection(&b).eq(a.intersection(&b)));
+    assert!(sa.difference(vb.iter()).eq(a.difference(&b)));
+    assert!(sa.symmetric_difference(&vb).eq(a.symmetric_difference(&b)));
+}
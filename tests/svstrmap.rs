@@ -0,0 +1,126 @@
+use dynamization::sorted_vec::{ SVStrMap, RebuildPolicy };
+use dynamization::strategy;
+use std::collections::BTreeMap;
+
+
+fn random_key<R: rand::Rng>(rng: &mut R) -> String {
+    // Short keys over a small alphabet share long prefixes.
+    let len = rng.gen_range(0, 6);
+
+    (0..len).map(|_| ['a', 'b', 'é', '/'][rng.gen_range(0, 4)]).collect()
+}
+
+
+#[test]
+fn test_assoc() {
+    test_assoc_strategy::<strategy::Binary>();
+    test_assoc_strategy::<strategy::SimpleBinary>();
+    test_assoc_strategy::<strategy::SkewBinary>();
+}
+
+fn test_assoc_strategy<S: strategy::Strategy>() {
+    use rand::{ Rng, SeedableRng };
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+    for &size in &[0, 1, 2, 10, 100, 1000, 5000] {
+        let mut map = SVStrMap::<i32, S>::default();
+        let mut btree = BTreeMap::<String, i32>::new();
+
+        for step in 0..size {
+            let key = random_key(&mut rng);
+
+            if rng.gen_range(0, 10) < 7 {
+                assert_eq!(map.insert(&key, step), btree.insert(key.clone(), step));
+            } else {
+                assert_eq!(map.remove(&key), btree.remove(&key));
+            }
+
+            assert_eq!(map.len(), btree.len());
+
+            let key = random_key(&mut rng);
+            assert_eq!(map.get(&key), btree.get(&key));
+        }
+
+        if let Some(value) = map.get_mut("ab") {
+            *value += 1;
+            *btree.get_mut("ab").unwrap() += 1;
+        }
+
+        assert!(map.iter().eq(btree.iter().map(|(k, v)| (k.clone(), v))));
+        assert!(map.iter().rev().eq(btree.iter().rev().map(|(k, v)| (k.clone(), v))));
+    }
+}
+
+
+#[test]
+fn test_rebuild_policy() {
+    use rand::{ Rng, SeedableRng };
+
+    let policies = [
+        RebuildPolicy::default(),
+        RebuildPolicy::Fraction(0.1),
+        RebuildPolicy::Count(0),
+        RebuildPolicy::PerUnit(0.25),
+        RebuildPolicy::Never,
+    ];
+
+    for &policy in &policies {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let mut map = SVStrMap::<i32>::new();
+        let mut btree = BTreeMap::<String, i32>::new();
+
+        map.set_rebuild_policy(policy);
+
+        for step in 0..3000 {
+            let key = random_key(&mut rng);
+
+            if rng.gen_range(0, 10) < 5 {
+                assert_eq!(map.insert(&key, step), btree.insert(key.clone(), step));
+            } else {
+                assert_eq!(map.remove(&key), btree.remove(&key));
+            }
+
+            assert_eq!(map.len(), btree.len());
+        }
+
+        assert_eq!(map.rebuild_policy(), policy);
+        assert!(map.iter().eq(btree.iter().map(|(k, v)| (k.clone(), v))));
+    }
+}
+
+
+#[test]
+fn test_prefix() {
+    use rand::{ Rng, SeedableRng };
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    let mut map = SVStrMap::<usize>::new();
+    let mut btree = BTreeMap::<String, usize>::new();
+
+    for step in 0..3000 {
+        let key = random_key(&mut rng);
+
+        if rng.gen_range(0, 10) < 8 {
+            map.insert(&key, step);
+            btree.insert(key, step);
+        } else {
+            map.remove(&key);
+            btree.remove(&key);
+        }
+    }
+
+    for prefix in &["", "a", "ab", "é", "é/", "/b/", "bbbbbb"] {
+        let expected: Vec<_> = btree.iter()
+            .filter(|(k, _)| k.starts_with(prefix))
+            .map(|(k, v)| (k.clone(), v))
+            .collect();
+
+        assert!(map.prefix(prefix).eq(expected.iter().cloned()));
+        assert!(map.prefix(prefix).rev().eq(expected.iter().rev().cloned()));
+    }
+
+    map.clear();
+    assert!(map.is_empty());
+    assert_eq!(map.iter().count(), 0);
+}
@@ -0,0 +1,158 @@
+use dynamization::{ Static, Singleton, Insert, Dynamic };
+use dynamization::buffered::Buffered;
+use dynamization::tiered::{ Tiered, Upgrade };
+use dynamization::strategy;
+
+const THRESHOLD: usize = 16;
+
+#[derive(Clone, Debug, Default)]
+struct Unsorted(Vec<i32>);
+
+#[derive(Clone, Debug)]
+struct Sorted(Vec<i32>);
+
+impl Static for Unsorted {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn merge_with(mut self, other: Self) -> Self {
+        self.0.extend(other.0);
+        self
+    }
+}
+
+impl Singleton for Unsorted {
+    type Item = i32;
+
+    fn singleton(item: Self::Item) -> Self {
+        Unsorted(vec![item])
+    }
+}
+
+impl Insert for Unsorted {
+    fn insert(&mut self, item: Self::Item) {
+        self.0.push(item);
+    }
+}
+
+impl Static for Sorted {
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn merge_with(mut self, other: Self) -> Self {
+        self.0.extend(other.0);
+        self.0.sort();
+        self
+    }
+}
+
+impl Singleton for Sorted {
+    type Item = i32;
+
+    fn singleton(item: Self::Item) -> Self {
+        Sorted(vec![item])
+    }
+}
+
+impl Insert for Sorted {
+    fn insert(&mut self, item: Self::Item) {
+        let index = self.0.partition_point(|&x| x <= item);
+        self.0.insert(index, item);
+    }
+}
+
+impl Upgrade<Sorted> for Unsorted {
+    const THRESHOLD: usize = THRESHOLD;
+
+    fn upgrade(mut self) -> Sorted {
+        self.0.sort();
+        Sorted(self.0)
+    }
+}
+
+
+#[test]
+fn test_tiered() {
+    test_tiered_strategy::<strategy::Binary>();
+    test_tiered_strategy::<strategy::SimpleBinary>();
+    test_tiered_strategy::<strategy::SkewBinary>();
+}
+
+fn test_tiered_strategy<S: strategy::Strategy>() {
+    use rand::{ Rng, SeedableRng };
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+    let mut dynamic = Dynamic::<Tiered<Unsorted, Sorted>, S>::new();
+    let mut inserted = Vec::new();
+
+    for _ in 0..1000 {
+        let x = rng.gen_range(0, 10000);
+
+        dynamic.insert(x);
+        inserted.push(x);
+
+        for unit in dynamic.units() {
+            match unit {
+                Tiered::Small(small) => {
+                    assert!(small.len() <= THRESHOLD);
+                }
+
+                Tiered::Large(large) => {
+                    assert!(large.len() > THRESHOLD);
+                    assert!(large.0.windows(2).all(|w| w[0] <= w[1]));
+                }
+            }
+        }
+
+        let y = rng.gen_range(0, 10000);
+
+        let count = dynamic.units().map(|unit| match unit {
+            Tiered::Small(small) => {
+                small.0.iter().filter(|&&z| z == y).count()
+            }
+
+            Tiered::Large(large) => {
+                let lo = large.0.partition_point(|&z| z < y);
+                let hi = large.0.partition_point(|&z| z <= y);
+                hi - lo
+            }
+        }).sum::<usize>();
+
+        assert_eq!(count, inserted.iter().filter(|&&z| z == y).count());
+    }
+
+    assert_eq!(dynamic.len(), inserted.len());
+}
+
+
+
+#[test]
+fn test_insert_upgrade() {
+    let mut unit = Tiered::<Unsorted, Sorted>::singleton(0);
+
+    for x in 1..THRESHOLD as i32 {
+        unit.insert(-x);
+        assert!(unit.is_small());
+    }
+
+    unit.insert(THRESHOLD as i32);
+    assert!(!unit.is_small());
+    assert!(unit.as_large().unwrap().0.windows(2).all(|w| w[0] <= w[1]));
+
+    let mut buffered = Buffered::<Tiered<Unsorted, Sorted>>::with_capacity(2 * THRESHOLD);
+
+    for x in 0..1000 {
+        buffered.insert(x % 37);
+
+        for unit in buffered.units() {
+            match unit {
+                Tiered::Small(small) => assert!(small.len() <= THRESHOLD),
+                Tiered::Large(large) => assert!(large.len() > THRESHOLD),
+            }
+        }
+    }
+
+    assert_eq!(buffered.len(), 1000);
+}
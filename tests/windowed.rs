@@ -0,0 +1,93 @@
+use dynamization::{ Static, Singleton };
+use dynamization::windowed::Windowed;
+use dynamization::strategy;
+
+#[derive(Clone, Debug)]
+struct Events {
+    vec: Vec<u64>,
+}
+
+impl Static for Events {
+    fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    fn merge_with(mut self, other: Self) -> Self {
+        self.vec.extend(other.vec);
+        self
+    }
+}
+
+impl Singleton for Events {
+    type Item = u64;
+
+    fn singleton(item: Self::Item) -> Self {
+        Events { vec: vec![item] }
+    }
+}
+
+
+#[test]
+fn test_expire() {
+    test_expire_strategy::<strategy::Chunked>();
+    test_expire_strategy::<strategy::Binary>();
+    test_expire_strategy::<strategy::SimpleBinary>();
+    test_expire_strategy::<strategy::SkewBinary>();
+}
+
+fn test_expire_strategy<S: strategy::Strategy>() {
+    let window = 100;
+    let mut events = Windowed::<Events, u64, S>::with_unit_count(4);
+
+    for time in 0..1000 {
+        events.insert(time, time);
+
+        if time % 7 == 0 && time >= window {
+            let start = time - window;
+            events.expire_before(&start);
+
+            let mut all: Vec<_> = events.units()
+                .flat_map(|unit| unit.container().vec.iter().cloned())
+                .collect();
+            all.sort();
+
+            assert!(all.ends_with(&(start..=time).collect::<Vec<_>>()));
+        }
+
+        if time % 31 == 0 && time >= window {
+            let start = time - window;
+            events.expire_before_with(&start, |unit, start| {
+                unit.vec.retain(|x| x >= start);
+            });
+
+            for unit in events.units() {
+                assert!(*unit.min_time() >= start);
+            }
+
+            assert_eq!(events.len() as u64, window + 1);
+        }
+    }
+}
+
+
+#[test]
+fn test_chunked_disjoint() {
+    let mut events = Windowed::<Events, u64>::with_unit_count(3);
+
+    for time in 0..500 {
+        events.insert(time, time);
+
+        let mut ranges: Vec<_> = events.units()
+            .map(|unit| (*unit.min_time(), *unit.max_time()))
+            .collect();
+        ranges.sort();
+
+        for pair in ranges.windows(2) {
+            assert!(pair[0].1 < pair[1].0);
+        }
+
+        for unit in events.units() {
+            assert!(unit.len() <= 8);
+        }
+    }
+}